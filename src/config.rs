@@ -0,0 +1,69 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Project specific choices of the conversion, usually read from a json file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Config {
+    /// Implement `Deref` and `DerefMut` to the parent embedded by `extends`
+    pub deref_parent: bool,
+    /// Implement `AsRef` to the parent embedded by `extends`
+    pub as_ref_parent: bool,
+    /// Rust types of TypeScript keywords and built-in types such as `"number": "i64"`
+    ///
+    /// Entries override the built-in table. Type arguments are appended to a mapped path without
+    /// its own, so `"Map": "BTreeMap"` turns `Map<K, V>` into `BTreeMap<K, V>`.
+    pub types: HashMap<String, String>,
+    /// Type of `number` fields inferred to hold integers
+    pub integer: String,
+    /// Types of `number` fields keyed by `Owner.field` or by the field name alone
+    pub numbers: HashMap<String, String>,
+    /// How interfaces take the fields of `extends` and intersections
+    pub parents: Parents,
+    /// How function type parameters are passed
+    pub callbacks: Callbacks,
+    /// How functions declared with several signatures are translated
+    pub overloads: Overloads
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Parents {
+    /// Copy the fields of the parents into the struct
+    Inline,
+    /// Embed each parent as a `#[serde(flatten)]` field
+    Flatten
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Callbacks {
+    /// `Box<dyn Fn(A) -> R + Send + Sync>`
+    Boxed,
+    /// A type parameter `F: Fn(A) -> R` of the function, while fields stay boxed
+    Generic
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Overloads {
+    /// One function per signature, suffixed with its string literal argument or its position
+    Suffixed,
+    /// One function taking an enum with a variant per signature
+    Enum
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            deref_parent: true,
+            as_ref_parent: true,
+            types: HashMap::new(),
+            integer: "i64".to_owned(),
+            numbers: HashMap::new(),
+            parents: Parents::Inline,
+            callbacks: Callbacks::Boxed,
+            overloads: Overloads::Suffixed
+        }
+    }
+}
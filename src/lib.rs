@@ -1,66 +1,80 @@
+pub mod config;
+mod translate;
 pub mod typescript;
 
 use proc_macro2::Span;
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     path,
     path::{Path, PathBuf}
 };
+use unicode_xid::UnicodeXID;
+
+pub use translate::{NumberChoice, NumberReason};
 
 pub fn convert(ts: typescript::Load<'_>, dir: &Path) -> anyhow::Result<Vec<(PathBuf, syn::File)>> {
+    Ok(convert_with(ts, dir, &config::Config::default())?.files)
+}
+
+/// Rust files with the choices made on the way
+pub struct Conversion {
+    pub files: Vec<(PathBuf, syn::File)>,
+    pub numbers: Vec<NumberChoice>
+}
+
+pub fn convert_with(
+    ts: typescript::Load<'_>,
+    dir: &Path,
+    config: &config::Config
+) -> anyhow::Result<Conversion> {
     let typescript::Load {
         root,
         parsed,
         children
     } = ts;
-    let files: Vec<_> = children.iter().map(|(k, _)| -> &Path { k }).collect();
+    let files: Vec<_> = children.keys().map(|k| -> &Path { k }).collect();
     let dests = destinations(root, &files, dir);
     if files.len() != 1 {
-        return Ok(vec![]);
+        return Ok(Conversion {
+            files: vec![],
+            numbers: vec![]
+        });
     }
     let dest = dests.get(root).unwrap();
-    let p = tmp(parsed.get(root).unwrap());
-    Ok(vec![(dest.to_owned(), p)])
+    let root_module = parsed.get(root).unwrap();
+    let ctx = translate::Context::new(config, root_module, parsed.values().map(|p| &p.ast))?;
+    let p = tmp(root_module, &ctx);
+    Ok(Conversion {
+        files: vec![(dest.to_owned(), p)],
+        numbers: ctx.number_choices()
+    })
 }
 
-fn tmp(parsed: &typescript::Parsed) -> syn::File {
+fn tmp(parsed: &typescript::Parsed, ctx: &translate::Context<'_>) -> syn::File {
     use swc_ecma_ast as ast;
     let typescript::Parsed { ast, .. } = parsed;
-    let items = ast.body.iter().fold(Vec::new(), |mut items, item| {
-        match item {
-            ast::ModuleItem::Stmt(ast::Stmt::Decl(ast::Decl::Fn(f))) => {
-                let attrs = vec![];
-                items.push(syn::Item::Fn(syn::ItemFn {
-                    attrs,
-                    vis: syn::Visibility::Inherited,
-                    sig: syn::Signature {
-                        constness: None,
-                        asyncness: None,
-                        unsafety: None,
-                        abi: None,
-                        fn_token: syn::token::Fn::default(),
-                        ident: ident(&f.ident),
-                        generics: syn::Generics {
-                            lt_token: None,
-                            params: syn::punctuated::Punctuated::default(),
-                            gt_token: None,
-                            where_clause: None
-                        },
-                        paren_token: syn::token::Paren::default(),
-                        inputs: syn::punctuated::Punctuated::default(),
-                        variadic: None,
-                        output: syn::ReturnType::Default
-                    },
-                    block: Box::new(syn::Block {
-                        brace_token: syn::token::Brace::default(),
-                        stmts: vec![]
-                    })
-                }));
+    let overloaded = translate::overloaded_fns(ast);
+    let mut emitted = HashSet::new();
+    let mut items = translate::decls(ast).fold(Vec::new(), |mut items, (vis, decl)| {
+        match decl {
+            ast::Decl::Fn(f) if overloaded.contains_key(f.ident.as_ref()) => {
+                let name = f.ident.as_ref();
+                if emitted.insert(name) {
+                    items.extend(ctx.overloaded_fn(name, vis, &overloaded[name]));
+                }
             }
+            ast::Decl::Fn(f) => items.push(syn::Item::Fn(ctx.function(f, vis))),
+            ast::Decl::TsInterface(i) => items.push(ctx.interface(i, vis)),
+            ast::Decl::TsTypeAlias(a) => items.push(ctx.type_alias(a, vis)),
+            ast::Decl::Class(c) => items.extend(ctx.class(c, vis)),
+            ast::Decl::TsEnum(e) => items.push(syn::Item::Enum(ctx.ts_enum(e, vis))),
             _ => {}
         }
         items
     });
+    items.extend(ctx.generated());
+    items.extend(ctx.error_enum());
+    translate::box_recursive(&mut items);
     syn::File {
         shebang: None,
         attrs: Vec::new(),
@@ -68,7 +82,31 @@ fn tmp(parsed: &typescript::Parsed) -> syn::File {
     }
 }
 
-fn ident(x: &swc_ecma_ast::Ident) -> syn::Ident { syn::Ident::new(x.as_ref(), Span::call_site()) }
+fn ident(x: &swc_ecma_ast::Ident) -> syn::Ident { ident_str(x.as_ref()) }
+
+/// Rust keywords become raw identifiers and characters that cannot appear in identifiers
+/// become `_`
+fn ident_str(s: &str) -> syn::Ident {
+    let mut s: String = s
+        .chars()
+        .map(|c| if c.is_xid_continue() { c } else { '_' })
+        .collect();
+    if s.is_empty() {
+        s.push('_');
+    }
+    if s == "_" || s.starts_with(|c: char| !c.is_xid_start() && c != '_') {
+        s.insert(0, '_');
+    }
+    match &*s {
+        "self" | "Self" | "super" | "crate" => {
+            syn::Ident::new(&format!("{}_", s), Span::call_site())
+        }
+        _ if syn::parse_str::<syn::Ident>(&s).is_err() => {
+            syn::Ident::new_raw(&s, Span::call_site())
+        }
+        _ => syn::Ident::new(&s, Span::call_site())
+    }
+}
 
 fn destinations(root: &Path, files: &[&Path], dir: &Path) -> HashMap<PathBuf, PathBuf> {
     let res: HashMap<_, _> = files
@@ -100,8 +138,8 @@ fn destinations(root: &Path, files: &[&Path], dir: &Path) -> HashMap<PathBuf, Pa
 }
 
 fn relative(root: &Path, file: &Path) -> PathBuf {
-    let l: Vec<_> = root.components().into_iter().collect();
-    let r: Vec<_> = file.components().into_iter().collect();
+    let l: Vec<_> = root.components().collect();
+    let r: Vec<_> = file.components().collect();
     let (cnt, _) = root
         .components()
         .zip(file.components())
@@ -155,7 +193,7 @@ pub mod test_utils {
         fs::create_dir_all(&betterty).unwrap();
         let dir = betterty.join(id);
         Command::new("git")
-            .args(&[
+            .args([
                 "clone",
                 "https://github.com/microsoft/playwright",
                 &dir.display().to_string()
@@ -298,6 +336,1017 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn can_convert_function_signature() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            function foo(a: string, b: number): boolean {}
+            function bar(xs: string[], ...rest: number[]): void {}
+            function baz(c = 1, d: Foo.Bar) {}
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                fn foo(a: String, b: f64) -> bool {}
+                fn bar(xs: Vec<String>, rest: Vec<f64>) {}
+                fn baz(c: serde_json::Value, d: Foo::Bar) {}
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_interface() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export interface Foo {
+                a: string;
+                fooBar: number;
+                innerHTML: boolean;
+                'x-y': Bar[];
+            }
+            interface Empty {}
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct Foo {
+                    pub a: String,
+                    pub foo_bar: f64,
+                    #[serde(rename = "innerHTML")]
+                    pub inner_html: bool,
+                    #[serde(rename = "x-y")]
+                    pub x_y: Vec<Bar>
+                }
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                struct Empty {}
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_rename_mangled_fields() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            interface Odd {
+                self: string;
+                '0': number;
+                '': boolean;
+                'x²': string;
+                type: string;
+            }
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                struct Odd {
+                    #[serde(rename = "self")]
+                    pub self_: String,
+                    #[serde(rename = "0")]
+                    pub _0: f64,
+                    #[serde(rename = "")]
+                    pub __: bool,
+                    #[serde(rename = "x²")]
+                    pub x_: String,
+                    pub r#type: String
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_optional() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export interface Options {
+                timeout?: number;
+                path: string | undefined;
+                state?: State | null;
+                url: string;
+            }
+            function foo(a?: string, b: number | null) {}
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct Options {
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub timeout: Option<i64>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub path: Option<String>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub state: Option<State>,
+                    pub url: String
+                }
+                fn foo(a: Option<String>, b: Option<f64>) {}
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_string_literal_union() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export type LifecycleEvent = 'load' | 'domcontentloaded' | 'networkidle';
+            type Mode = "no-preference" | "reduce";
+            type Name = string;
+            type Op = '*' | '+' | 'a-b' | 'aB';
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+                pub enum LifecycleEvent {
+                    #[serde(rename = "load")]
+                    Load,
+                    #[serde(rename = "domcontentloaded")]
+                    Domcontentloaded,
+                    #[serde(rename = "networkidle")]
+                    Networkidle
+                }
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+                enum Mode {
+                    #[serde(rename = "no-preference")]
+                    NoPreference,
+                    #[serde(rename = "reduce")]
+                    Reduce
+                }
+                type Name = String;
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+                enum Op {
+                    #[serde(rename = "*")]
+                    Empty,
+                    #[serde(rename = "+")]
+                    Empty2,
+                    #[serde(rename = "a-b")]
+                    AB,
+                    #[serde(rename = "aB")]
+                    AB2
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_enum() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export enum Direction { Up = 1, Down, Left = 1 << 4, Right }
+            const enum Color { Red = 'RED', Green = 'GREEN' }
+            enum Mask { A = -1, B, C = ~0 }
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                #[derive(
+                    Debug,
+                    Clone,
+                    Copy,
+                    PartialEq,
+                    Eq,
+                    serde_repr::Serialize_repr,
+                    serde_repr::Deserialize_repr
+                )]
+                #[repr(i32)]
+                pub enum Direction {
+                    Up = 1,
+                    Down = 2,
+                    Left = 16,
+                    Right = 17
+                }
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+                enum Color {
+                    #[serde(rename = "RED")]
+                    Red,
+                    #[serde(rename = "GREEN")]
+                    Green
+                }
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+                enum Mask {
+                    A,
+                    B,
+                    C
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_discriminated_union() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            interface Blob { kind: 'blob'; byteLength: number; }
+            export type Value = { kind: 'number', n: number } | { kind: 'text', text?: string } | Blob;
+            "#
+        )?;
+        assert_eq!(
+            rs.items[1],
+            syn::parse_str(
+                r#"
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(tag = "kind")]
+                pub enum Value {
+                    #[serde(rename = "number", rename_all = "camelCase")]
+                    Number { n: f64 },
+                    #[serde(rename = "text", rename_all = "camelCase")]
+                    Text {
+                        #[serde(skip_serializing_if = "Option::is_none")]
+                        text: Option<String>
+                    },
+                    #[serde(rename = "blob", rename_all = "camelCase")]
+                    Blob { byte_length: i64 }
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_class() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export class Counter {
+                static DEFAULT = 1;
+                static EMPTY: string[] = [];
+                private count: number = 0;
+                name?: string;
+                label: string;
+                constructor(private readonly page: Page, initial: number) {
+                    this.label = 'counter';
+                }
+                increment(by: number): void { this.count += by; }
+                get value(): number { return this.count; }
+                set value(v: number) { this.count = v; }
+                static create(): Counter { return new Counter(); }
+                #reset() { this.count = 0; }
+            }
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                pub struct Counter {
+                    count: i64,
+                    pub name: Option<String>,
+                    pub label: String,
+                    page: Page
+                }
+                impl Counter {
+                    pub const DEFAULT: f64 = 1.0;
+                    pub fn empty() -> Vec<String> { vec![] }
+                    pub fn new(page: Page, initial: f64) -> Self {
+                        let label = "counter";
+                        Self { count: 0, name: None, label, page }
+                    }
+                    pub fn increment(&mut self, by: f64) { self.count += by; }
+                    pub fn value(&self) -> f64 { return self.count; }
+                    pub fn set_value(&mut self, v: f64) { self.count = v; }
+                    pub fn create() -> Counter { return Counter::new(); }
+                    fn reset(&mut self) { self.count = 0; }
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_construct_derived_class() -> anyhow::Result<()> {
+        let config = config::Config {
+            deref_parent: false,
+            as_ref_parent: false,
+            ..Default::default()
+        };
+        let rs = convert_source_with(
+            r#"
+            export class Base {
+                constructor(public id: string) {}
+            }
+            export class Child extends Base {
+                label: string;
+                ready: boolean;
+                constructor(id: string, label: string) {
+                    super(id);
+                    this.label = label.trim();
+                    log(label);
+                }
+            }
+            "#,
+            &config
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                pub struct Base {
+                    pub id: String
+                }
+                impl Base {
+                    pub fn new(id: String) -> Self {
+                        Self { id }
+                    }
+                }
+                pub struct Child {
+                    pub base: Base,
+                    pub label: String,
+                    pub ready: bool
+                }
+                impl Child {
+                    pub fn new(id: String, label: String) -> Self {
+                        let base = Base::new(id);
+                        let label = label.trim();
+                        log(label);
+                        Self { base, label, ready: Default::default() }
+                    }
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_implements() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export interface Closable {
+                readonly closed: boolean;
+                close(reason?: string): void;
+            }
+            export class Browser implements Closable {
+                get closed(): boolean { return false; }
+                close(reason?: string) {}
+                version(): string {}
+            }
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                pub trait Closable {
+                    fn closed(&self) -> bool;
+                    fn close(&self, reason: Option<String>);
+                }
+                pub struct Browser {}
+                impl Browser {
+                    pub fn version(&self) -> String {}
+                }
+                impl Closable for Browser {
+                    fn closed(&self) -> bool { return false; }
+                    fn close(&self, reason: Option<String>) {}
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_implement_traits_with_fields_and_mutations() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export interface Closable {
+                readonly closed: boolean;
+                name?: string;
+                close(reason?: string): void;
+            }
+            export class Page implements Closable {
+                closed: boolean = false;
+                name?: string;
+                close(reason?: string) { this.closed = true; }
+            }
+            export class Frame implements Closable {
+                name?: string;
+                get closed(): boolean { return true; }
+                close(reason?: string) {}
+            }
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                pub trait Closable {
+                    fn closed(&self) -> bool;
+                    fn name(&self) -> Option<String>;
+                    fn close(&mut self, reason: Option<String>);
+                }
+                pub struct Page {
+                    pub closed: bool,
+                    pub name: Option<String>
+                }
+                impl Closable for Page {
+                    fn closed(&self) -> bool {
+                        self.closed
+                    }
+                    fn name(&self) -> Option<String> {
+                        self.name.clone()
+                    }
+                    fn close(&mut self, reason: Option<String>) {
+                        self.closed = true;
+                    }
+                }
+                pub struct Frame {
+                    pub name: Option<String>
+                }
+                impl Closable for Frame {
+                    fn name(&self) -> Option<String> {
+                        self.name.clone()
+                    }
+                    fn closed(&self) -> bool {
+                        return true;
+                    }
+                    fn close(&mut self, reason: Option<String>) {}
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_abstract_class() -> anyhow::Result<()> {
+        let config = config::Config {
+            deref_parent: false,
+            as_ref_parent: false,
+            ..Default::default()
+        };
+        let rs = convert_source_with(
+            r#"
+            export abstract class Shape {
+                name: string;
+                tags: string[];
+                abstract readonly sides: number;
+                abstract area(): number;
+                describe(): string { return this.name; }
+                rename(name: string) {
+                    this.name = name;
+                    this.tags.push(name);
+                }
+                static unit(): number { return 1; }
+            }
+            export class Square extends Shape {
+                get sides(): number { return 4; }
+                area(): number { return 1; }
+                size(): number { return 1; }
+            }
+            "#,
+            &config
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                pub struct ShapeBase {
+                    pub name: String,
+                    pub tags: Vec<String>
+                }
+                impl ShapeBase {
+                    pub fn unit() -> f64 { return 1.0; }
+                }
+                pub trait Shape {
+                    fn base(&self) -> &ShapeBase;
+                    fn base_mut(&mut self) -> &mut ShapeBase;
+                    fn sides(&self) -> f64;
+                    fn area(&self) -> f64;
+                    fn describe(&self) -> String { return self.base().name; }
+                    fn rename(&mut self, name: String) {
+                        self.base_mut().name = name;
+                        self.base_mut().tags.push(name);
+                    }
+                }
+                pub struct Square {
+                    pub shape_base: ShapeBase
+                }
+                impl Square {
+                    pub fn size(&self) -> f64 { return 1.0; }
+                }
+                impl Shape for Square {
+                    fn base(&self) -> &ShapeBase { &self.shape_base }
+                    fn base_mut(&mut self) -> &mut ShapeBase { &mut self.shape_base }
+                    fn sides(&self) -> f64 { return 4.0; }
+                    fn area(&self) -> f64 { return 1.0; }
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_generics() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            interface Disposable { dispose(): void; }
+            export interface Page<T = string> { items: Map<string, T>; }
+            export type Handler<T extends Disposable> = Page<T>;
+            export function first<K extends string, V>(m: Map<K, V>): V {}
+            export class Pool<T extends Disposable> {
+                items: T[];
+                map<U>(f: Handler<T>): U {}
+            }
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                trait Disposable {
+                    fn dispose(&self);
+                }
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct Page<T = String> {
+                    pub items: std::collections::HashMap<String, T>
+                }
+                pub type Handler<T> = Page<T>;
+                pub fn first<K: AsRef<str>, V>(m: std::collections::HashMap<K, V>) -> V {}
+                pub struct Pool<T: Disposable> {
+                    pub items: Vec<T>
+                }
+                impl<T: Disposable> Pool<T> {
+                    pub fn map<U>(&self, f: Handler<T>) -> U {}
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_async() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export async function launch(): Promise<Browser> {}
+            export async function close() {}
+            export interface Page {
+                goto(url: string): Promise<void>;
+            }
+            export class Frame {
+                async title(): Promise<string> {}
+                waitFor(): Promise<Frame> {}
+            }
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                pub async fn launch() -> Browser {}
+                pub async fn close() {}
+                pub trait Page {
+                    async fn goto(&self, url: String);
+                }
+                pub struct Frame {}
+                impl Frame {
+                    pub async fn title(&self) -> String {}
+                    pub async fn wait_for(&self) -> Frame {}
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_map_types() -> anyhow::Result<()> {
+        let source = r#"
+            export interface Response {
+                status: number;
+                body: Buffer;
+                date: Date;
+                headers: { [name: string]: string };
+                cookies: Record<string, Cookie>;
+                frames: Array<Frame>;
+                extra: unknown;
+            }
+            "#;
+        assert_eq!(
+            convert_source(source)?,
+            syn::parse_str(
+                r#"
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct Response {
+                    pub status: i64,
+                    pub body: Vec<u8>,
+                    pub date: chrono::DateTime<chrono::Utc>,
+                    pub headers: std::collections::HashMap<String, String>,
+                    pub cookies: std::collections::HashMap<String, Cookie>,
+                    pub frames: Vec<Frame>,
+                    pub extra: serde_json::Value
+                }
+                "#
+            )?
+        );
+        let config = config::Config {
+            types: vec![
+                ("number", "i64"),
+                ("Date", "String"),
+                ("Record", "indexmap::IndexMap"),
+            ]
+            .into_iter()
+            .map(|(ts, rs)| (ts.to_owned(), rs.to_owned()))
+            .collect(),
+            ..Default::default()
+        };
+        assert_eq!(
+            convert_source_with(source, &config)?,
+            syn::parse_str(
+                r#"
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct Response {
+                    pub status: i64,
+                    pub body: Vec<u8>,
+                    pub date: String,
+                    pub headers: indexmap::IndexMap<String, String>,
+                    pub cookies: indexmap::IndexMap<String, Cookie>,
+                    pub frames: Vec<Frame>,
+                    pub extra: serde_json::Value
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_infer_numbers() -> anyhow::Result<()> {
+        let source = r#"
+            export interface Viewport {
+                width: number;
+                deviceScaleFactor: number;
+                /** Maximum time in milliseconds */
+                wait?: number;
+                opacity: number;
+                x: number;
+                y: number;
+            }
+            export type ViewportPatch = Partial<Viewport>;
+            const viewport = { x: 10, y: 2, opacity: 0.5 };
+            "#;
+        let mut config = config::Config::default();
+        config
+            .numbers
+            .insert("Viewport.y".to_owned(), "u8".to_owned());
+        let tmp = TempDir::new("betterty")?;
+        let p = tmp.path().join("index.ts");
+        File::create(&p)?.write_all(source.as_bytes())?;
+        let mut conversion = convert_with(typescript::load(&p)?, Path::new("/"), &config)?;
+        assert_eq!(
+            conversion.files.remove(0).1,
+            syn::parse_str(
+                r#"
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct Viewport {
+                    pub width: i64,
+                    pub device_scale_factor: f64,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub wait: Option<i64>,
+                    pub opacity: f64,
+                    pub x: i64,
+                    pub y: u8
+                }
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct ViewportPatch {
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub width: Option<i64>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub device_scale_factor: Option<f64>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub wait: Option<i64>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub opacity: Option<f64>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub x: Option<i64>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub y: Option<u8>
+                }
+                "#
+            )?
+        );
+        let reasons: Vec<_> = conversion
+            .numbers
+            .iter()
+            .map(|c| (&*c.field, &c.reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            [
+                ("Viewport.width", &NumberReason::Name),
+                ("Viewport.deviceScaleFactor", &NumberReason::Default),
+                (
+                    "Viewport.wait",
+                    &NumberReason::Doc("in milliseconds".to_owned())
+                ),
+                ("Viewport.opacity", &NumberReason::FractionalLiteral),
+                ("Viewport.x", &NumberReason::IntegerLiteral),
+                ("Viewport.y", &NumberReason::Override)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_tuples_and_indexed_access() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            interface Geometry { size: { box: [number, number] }; label?: string; }
+            export type Point = [number, number];
+            export type Single = [string];
+            export type Args = [string, boolean?, ...number[]];
+            export type Names = readonly string[];
+            export type Frames = ReadonlyArray<Frame>;
+            export type Bounds = Geometry['size']['box'];
+            export type Label = Geometry['label'];
+            export type Unknown = Other['x'];
+            "#
+        )?;
+        assert_eq!(
+            rs.items[1..],
+            syn::parse_str::<syn::File>(
+                r#"
+                pub type Point = (f64, f64);
+                pub type Single = (String,);
+                pub type Args = (String, Option<bool>, Vec<f64>);
+                pub type Names = Vec<String>;
+                pub type Frames = Vec<Frame>;
+                pub type Bounds = (f64, f64);
+                pub type Label = Option<String>;
+                pub type Unknown = serde_json::Value;
+                "#
+            )?
+            .items[..]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_interface_extends() -> anyhow::Result<()> {
+        let source = r#"
+            interface TimeoutOptions { timeout?: number; }
+            interface NavigateOptions extends TimeoutOptions { referer?: string; }
+            export interface GotoOptions extends NavigateOptions, External { url: string; }
+            export type ClickOptions = TimeoutOptions & { force: boolean };
+            interface Closable { close(): void; }
+            export interface Disposable extends Closable { dispose(): void; }
+            "#;
+        let expected = |parents: &str| {
+            format!(
+                r#"
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct GotoOptions {{
+                    {}
+                    #[serde(flatten)]
+                    pub external: External,
+                    pub url: String
+                }}
+                "#,
+                parents
+            )
+        };
+        let rs = convert_source(source)?;
+        assert_eq!(
+            rs.items[2..4],
+            syn::parse_str::<syn::File>(&format!(
+                "{}{}",
+                expected(
+                    r#"
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub timeout: Option<i64>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub referer: Option<String>,"#
+                ),
+                r#"
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct ClickOptions {
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub timeout: Option<i64>,
+                    pub force: bool
+                }
+                "#
+            ))?
+            .items[..]
+        );
+        assert_eq!(
+            rs.items[5],
+            syn::parse_str(
+                r#"
+                pub trait Disposable: Closable {
+                    fn dispose(&self);
+                }
+                "#
+            )?
+        );
+        let config = config::Config {
+            parents: config::Parents::Flatten,
+            ..Default::default()
+        };
+        assert_eq!(
+            convert_source_with(source, &config)?.items[2],
+            syn::parse_str(&expected(
+                r#"
+                #[serde(flatten)]
+                pub navigate_options: NavigateOptions,"#
+            ))?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_expand_utility_types() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            interface Base { id: string; }
+            interface Cookie extends Base { name: string; value?: string; secure: boolean; }
+            export type CookieParam = Partial<Pick<Cookie, 'name' | 'value'>>;
+            export type StrictCookie = Required<Omit<Cookie, 'secure'>>;
+            export type FrozenCookie = Readonly<Cookie>;
+            export function set(cookie: Pick<Cookie, 'name'>) {}
+            "#
+        )?;
+        let fields = r#"
+            pub id: String,
+            pub name: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub value: Option<String>,
+            pub secure: bool
+            "#;
+        assert_eq!(
+            rs.items[2..],
+            syn::parse_str::<syn::File>(&format!(
+                r#"
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct CookieParam {{
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub name: Option<String>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub value: Option<String>
+                }}
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct StrictCookie {{
+                    pub id: String,
+                    pub name: String,
+                    pub value: String
+                }}
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct FrozenCookie {{ {} }}
+                pub fn set(cookie: PickCookieName) {{}}
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct PickCookieName {{
+                    pub name: String
+                }}
+                "#,
+                fields
+            ))?
+            .items[..]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_callbacks() -> anyhow::Result<()> {
+        let source = r#"
+            export interface Route { handler: (route: Route, request?: Request) => void; }
+            export function on(event: string, listener: (page: Page) => boolean) {}
+            export function route<T>(url: T, handler?: (route: Route) => void, fallback?: () => void) {}
+            "#;
+        assert_eq!(
+            convert_source(source)?.items[1..],
+            syn::parse_str::<syn::File>(
+                r#"
+                pub fn on(event: String, listener: Box<dyn Fn(Page) -> bool + Send + Sync>) {}
+                pub fn route<T>(
+                    url: T,
+                    handler: Option<Box<dyn Fn(Route) + Send + Sync>>,
+                    fallback: Option<Box<dyn Fn() + Send + Sync>>
+                ) {}
+                "#
+            )?
+            .items[..]
+        );
+        let config = config::Config {
+            callbacks: config::Callbacks::Generic,
+            ..Default::default()
+        };
+        assert_eq!(
+            convert_source_with(source, &config)?,
+            syn::parse_str(
+                r#"
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct Route {
+                    pub handler: Box<dyn Fn(Route, Option<Request>) + Send + Sync>
+                }
+                pub fn on<F: Fn(Page) -> bool>(event: String, listener: F) {}
+                pub fn route<T, F: Fn(Route), F1: Fn()>(url: T, handler: Option<F>, fallback: Option<F1>) {}
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_inheritance() -> anyhow::Result<()> {
+        let source = r#"
+            export class Page extends ChannelOwner {
+                url(): string {}
+            }
+            "#;
+        let expected = r#"
+            pub struct Page {
+                pub channel_owner: ChannelOwner
+            }
+            impl Page {
+                pub fn url(&self) -> String {}
+            }
+            "#;
+        assert_eq!(
+            convert_source(source)?,
+            syn::parse_str(&format!(
+                "{}{}",
+                expected,
+                r#"
+                impl std::ops::Deref for Page {
+                    type Target = ChannelOwner;
+                    fn deref(&self) -> &ChannelOwner { &self.channel_owner }
+                }
+                impl std::ops::DerefMut for Page {
+                    fn deref_mut(&mut self) -> &mut ChannelOwner { &mut self.channel_owner }
+                }
+                impl AsRef<ChannelOwner> for Page {
+                    fn as_ref(&self) -> &ChannelOwner { &self.channel_owner }
+                }
+                "#
+            ))?
+        );
+        let config = config::Config {
+            deref_parent: false,
+            as_ref_parent: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            convert_source_with(source, &config)?,
+            syn::parse_str(expected)?
+        );
+        Ok(())
+    }
+
+    fn convert_source(source: &str) -> anyhow::Result<syn::File> {
+        convert_source_with(source, &config::Config::default())
+    }
+
+    fn convert_source_with(source: &str, config: &config::Config) -> anyhow::Result<syn::File> {
+        let tmp = TempDir::new("betterty")?;
+        let p = tmp.path().join("index.ts");
+        File::create(&p)?.write_all(source.as_bytes())?;
+        let loaded = typescript::load(&p)?;
+        let mut rs = convert_with(loaded, Path::new("/"), config)?;
+        Ok(rs.files.remove(0).1)
+    }
+
     #[test]
     fn can_convert_basic() -> anyhow::Result<()> {
         let dir = no_dependences("can_convert_basic")?;
@@ -338,9 +1387,966 @@ mod tests {
             dir.join("src/server/usKeyboardLayout.ts"),
             dir.join("src/utils/errors.ts")
         ] {
-            let is_empty = empties.get(&p as &Path).is_some();
+            let is_empty = empties.contains(&p as &Path);
             assert!(is_empty, "{} has dependencies", p.display());
         }
         Ok(dir)
     }
+
+    #[test]
+    fn can_convert_overloads() -> anyhow::Result<()> {
+        let source = r#"
+            export function waitFor(event: 'load', timeout: number): Promise<void>;
+            export function waitFor(event: 'request', url: string): Promise<Request>;
+            export async function waitFor(event: string, arg: any): Promise<any> {
+                wait(event, arg);
+            }
+            export function pick(x: number): number;
+            export function pick(x: string, y: boolean): number;
+            export function pick(x: any, y?: boolean): number { return 1; }
+            export class Page {
+                url: string;
+                goto(url: string): void;
+                goto(url: URL, referer: string): void;
+                goto(target: any, referer?: string) { this.url = target; }
+            }
+            export interface Emitter {
+                on(event: 'close', listener: () => void): void;
+                on(event: 'data', listener: (chunk: string) => void): void;
+            }
+            export class Socket implements Emitter {
+                on(event: 'close', listener: () => void): void;
+                on(event: 'data', listener: (chunk: string) => void): void;
+                on(event: string, listener: any) { listen(event, listener); }
+            }
+            "#;
+        assert_eq!(
+            convert_source(source)?,
+            syn::parse_str(
+                r#"
+                pub async fn wait_for_load(timeout: f64) {
+                    let event = "load";
+                    let arg = timeout;
+                    wait(event, arg);
+                }
+                pub async fn wait_for_request(url: String) -> Request {
+                    let event = "request";
+                    let arg = url;
+                    wait(event, arg);
+                }
+                pub fn pick(x: f64) -> f64 {
+                    let y = None;
+                    return 1.0;
+                }
+                pub fn pick2(x: String, y: bool) -> f64 {
+                    return 1.0;
+                }
+                pub struct Page {
+                    pub url: String
+                }
+                impl Page {
+                    pub fn goto(&mut self, url: String) {
+                        let target = url;
+                        let referer = None;
+                        self.url = target;
+                    }
+                    pub fn goto2(&mut self, url: URL, referer: String) {
+                        let target = url;
+                        self.url = target;
+                    }
+                }
+                pub trait Emitter {
+                    fn on_close(&self, listener: Box<dyn Fn() + Send + Sync>);
+                    fn on_data(&self, listener: Box<dyn Fn(String) + Send + Sync>);
+                }
+                pub struct Socket {}
+                impl Emitter for Socket {
+                    fn on_close(&self, listener: Box<dyn Fn() + Send + Sync>) {
+                        let event = "close";
+                        listen(event, listener);
+                    }
+                    fn on_data(&self, listener: Box<dyn Fn(String) + Send + Sync>) {
+                        let event = "data";
+                        listen(event, listener);
+                    }
+                }
+                "#
+            )?
+        );
+        let config = config::Config {
+            overloads: config::Overloads::Enum,
+            ..Default::default()
+        };
+        assert_eq!(
+            convert_source_with(source, &config)?,
+            syn::parse_str(
+                r#"
+                pub enum WaitForArgs {
+                    Load { timeout: f64 },
+                    Request { url: String }
+                }
+                pub async fn wait_for(args: WaitForArgs) -> serde_json::Value {
+                    match args {
+                        WaitForArgs::Load { timeout } => {
+                            let event = "load";
+                            let arg = timeout;
+                            wait(event, arg);
+                        }
+                        WaitForArgs::Request { url } => {
+                            let event = "request";
+                            let arg = url;
+                            wait(event, arg);
+                        }
+                    }
+                }
+                pub enum PickArgs {
+                    X { x: f64 },
+                    XY { x: String, y: bool }
+                }
+                pub fn pick(args: PickArgs) -> f64 {
+                    match args {
+                        PickArgs::X { x } => {
+                            let y = None;
+                            return 1.0;
+                        }
+                        PickArgs::XY { x, y } => {
+                            return 1.0;
+                        }
+                    }
+                }
+                pub struct Page {
+                    pub url: String
+                }
+                pub enum PageGotoArgs {
+                    Url { url: String },
+                    UrlReferer { url: URL, referer: String }
+                }
+                impl Page {
+                    pub fn goto(&mut self, args: PageGotoArgs) {
+                        match args {
+                            PageGotoArgs::Url { url } => {
+                                let target = url;
+                                let referer = None;
+                                self.url = target;
+                            }
+                            PageGotoArgs::UrlReferer { url, referer } => {
+                                let target = url;
+                                self.url = target;
+                            }
+                        }
+                    }
+                }
+                pub trait Emitter {
+                    fn on(&self, args: EmitterOnArgs);
+                }
+                pub struct Socket {}
+                impl Emitter for Socket {
+                    fn on(&self, args: EmitterOnArgs) {
+                        match args {
+                            EmitterOnArgs::Close { listener } => {
+                                let event = "close";
+                                listen(event, listener);
+                            }
+                            EmitterOnArgs::Data { listener } => {
+                                let event = "data";
+                                listen(event, listener);
+                            }
+                        }
+                    }
+                }
+                pub enum EmitterOnArgs {
+                    Close { listener: Box<dyn Fn() + Send + Sync> },
+                    Data { listener: Box<dyn Fn(String) + Send + Sync> }
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_single_overloads() -> anyhow::Result<()> {
+        let source = r#"
+            export function parse(input: string): Url;
+            export function parse(input: any): Url { return new Url(input); }
+            export class Url {
+                href: string;
+                constructor(href: string);
+                constructor(href: any) { this.href = href; }
+                join(path: string): string;
+                join(path: any): string { return path; }
+            }
+            export class Point {
+                x: number;
+                constructor(x: number);
+                constructor(x: string, radix: number);
+                constructor(x: any, radix?: number) { this.x = parse(x, radix); }
+            }
+            "#;
+        assert_eq!(
+            convert_source(source)?,
+            syn::parse_str(
+                r#"
+                pub fn parse(input: String) -> Url {
+                    return Url::new(input);
+                }
+                pub struct Url {
+                    pub href: String
+                }
+                impl Url {
+                    pub fn new(href: String) -> Self {
+                        let href = href;
+                        Self { href }
+                    }
+                    pub fn join(&self, path: String) -> String {
+                        return path;
+                    }
+                }
+                pub struct Point {
+                    pub x: f64
+                }
+                impl Point {
+                    pub fn new(x: f64) -> Self {
+                        let radix = None;
+                        let x = parse(x, radix);
+                        Self { x }
+                    }
+                    pub fn new2(x: String, radix: f64) -> Self {
+                        let x = parse(x, radix);
+                        Self { x }
+                    }
+                }
+                "#
+            )?
+        );
+        let config = config::Config {
+            overloads: config::Overloads::Enum,
+            ..Default::default()
+        };
+        assert_eq!(
+            convert_source_with(source, &config)?,
+            syn::parse_str(
+                r#"
+                pub enum ParseArgs {
+                    Input { input: String }
+                }
+                pub fn parse(args: ParseArgs) -> Url {
+                    match args {
+                        ParseArgs::Input { input } => {
+                            return Url::new(input);
+                        }
+                    }
+                }
+                pub struct Url {
+                    pub href: String
+                }
+                pub enum UrlJoinArgs {
+                    Path { path: String }
+                }
+                pub enum UrlNewArgs {
+                    Href { href: String }
+                }
+                impl Url {
+                    pub fn new(args: UrlNewArgs) -> Self {
+                        match args {
+                            UrlNewArgs::Href { href } => {
+                                let href = href;
+                                Self { href }
+                            }
+                        }
+                    }
+                    pub fn join(&self, args: UrlJoinArgs) -> String {
+                        match args {
+                            UrlJoinArgs::Path { path } => {
+                                return path;
+                            }
+                        }
+                    }
+                }
+                pub struct Point {
+                    pub x: f64
+                }
+                pub enum PointNewArgs {
+                    X { x: f64 },
+                    XRadix { x: String, radix: f64 }
+                }
+                impl Point {
+                    pub fn new(args: PointNewArgs) -> Self {
+                        match args {
+                            PointNewArgs::X { x } => {
+                                let radix = None;
+                                let x = parse(x, radix);
+                                Self { x }
+                            }
+                            PointNewArgs::XRadix { x, radix } => {
+                                let x = parse(x, radix);
+                                Self { x }
+                            }
+                        }
+                    }
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_box_recursive_types() -> anyhow::Result<()> {
+        let source = r#"
+            export interface AXNode { name: string; children?: AXNode[]; parent?: AXNode; }
+            export interface Left { right?: Right; }
+            export interface Right { left: Left; pair: [Left, string]; }
+            "#;
+        assert_eq!(
+            convert_source(source)?,
+            syn::parse_str(
+                r#"
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct AXNode {
+                    pub name: String,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub children: Option<Vec<AXNode>>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub parent: Option<Box<AXNode>>
+                }
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct Left {
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    pub right: Option<Box<Right>>
+                }
+                #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                pub struct Right {
+                    pub left: Left,
+                    pub pair: (Left, String)
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_statements() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export function run(items: string[], ready: boolean): boolean {
+                const first = items;
+                let done: boolean = false;
+                let pending;
+                let [head, tail] = first;
+                if (ready) {
+                    return true;
+                } else if (done) {
+                    return false;
+                } else {}
+                while (ready) { continue; }
+                do { break; } while (done);
+                outer: for (let i = 0; ready;) {
+                    for (const item of items) { continue outer; }
+                    break outer;
+                }
+                for (;;) break;
+                return done;
+            }
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                pub fn run(items: Vec<String>, ready: bool) -> bool {
+                    let first = items;
+                    let mut done: bool = false;
+                    let mut pending;
+                    let (mut head, mut tail) = first;
+                    if ready {
+                        return true;
+                    } else if done {
+                        return false;
+                    } else {}
+                    while ready { continue; }
+                    loop {
+                        break;
+                        if !done {
+                            break;
+                        }
+                    }
+                    {
+                        let mut i = 0;
+                        'outer: while ready {
+                            for item in items { continue 'outer; }
+                            break 'outer;
+                        }
+                    }
+                    loop { break; }
+                    return done;
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_continue_loops_with_updates() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export function skip(items: string[], ready: boolean): void {
+                for (let i = 0; ready; i++) {
+                    if (ready) continue;
+                    for (const item of items) { continue; }
+                    break;
+                }
+                outer: for (let i = 0; ready; i++) {
+                    while (ready) { continue outer; }
+                }
+                do {
+                    if (ready) { continue; }
+                } while (ready);
+            }
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                pub fn skip(items: Vec<String>, ready: bool) {
+                    {
+                        let mut i = 0;
+                        'looped: while ready {
+                            'body: {
+                                if ready { break 'body; }
+                                for item in items { continue; }
+                                break 'looped;
+                            }
+                            i += 1;
+                        }
+                    }
+                    {
+                        let mut i = 0;
+                        'outer: while ready {
+                            'body: {
+                                while ready { break 'body; }
+                            }
+                            i += 1;
+                        }
+                    }
+                    loop {
+                        'body: {
+                            if ready { break 'body; }
+                        }
+                        if !ready {
+                            break;
+                        }
+                    }
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_number_literals() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export function limits(): number[] {
+                return [1, 1.5, 1e20, 1e400, -1e400];
+            }
+            export function scale(count: number, flags: string[]): number {
+                let x: number = 1;
+                x = 2;
+                x++;
+                const bits = 2 ** 8;
+                const big = 2 ** 64;
+                if (count > 0 && flags.length > 1) return 2 ** 8;
+                return count * 2 + x;
+            }
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                pub fn limits() -> Vec<f64> {
+                    return vec![1.0, 1.5, 100000000000000000000.0, f64::INFINITY, -f64::INFINITY];
+                }
+                pub fn scale(count: f64, flags: Vec<String>) -> f64 {
+                    let mut x: f64 = 1.0;
+                    x = 2.0;
+                    x += 1.0;
+                    let bits = i64::pow(2, 8);
+                    let big = (2f64).powf(64f64);
+                    if count > 0.0 && flags.length > 1 {
+                        return (2f64).powf(8f64);
+                    }
+                    return count * 2.0 + x;
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_expressions() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export class Frame extends ChannelOwner {
+                async goto(url: string, options: Options): Promise<string> {
+                    const timeout = options.timeout ?? 30000;
+                    let retries = -(1 + 2) * 3 - (4 - 5);
+                    retries++;
+                    const root = (retries - 1) ** 0.5;
+                    const ok = !(retries > 0 && timeout !== 0) || this.#detached;
+                    const name = ok ? `frame "${url}" {${retries}}` : 'it\'s\n';
+                    const response = await super.send('goto', this.pages[0], new Map());
+                    return response.headers['content-type'] + 2 ** 8;
+                }
+            }
+            "#
+        )?;
+        assert_eq!(
+            rs.items[1],
+            syn::parse_str(
+                r#"
+                impl Frame {
+                    pub async fn goto(&self, url: String, options: Options) -> String {
+                        let timeout = options.timeout.unwrap_or(30000);
+                        let mut retries = -(1 + 2) * 3 - (4 - 5);
+                        retries += 1;
+                        let root = ((retries - 1) as f64).powf(0.5f64);
+                        let ok = !(retries > 0 && timeout != 0) || self.detached;
+                        let name = if ok {
+                            format!("frame \"{}\" {{{}}}", url, retries)
+                        } else {
+                            "it's\n"
+                        };
+                        let response = self.channel_owner.send("goto", self.pages[0], std::collections::HashMap::new()).await;
+                        return response.headers["content-type"] + i64::pow(2, 8);
+                    }
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_switch() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export type LifecycleEvent = 'load' | 'domcontentloaded' | 'networkidle';
+            export enum Color { Red = 'red', Green = 'green' }
+            export function handle(event: LifecycleEvent, color: Color, code: number, name: string): number {
+                switch (event) {
+                    case 'load':
+                    case 'domcontentloaded':
+                        return 1;
+                    case 'networkidle':
+                        code = 2;
+                        break;
+                }
+                switch (color) {
+                    case Color.Red: break;
+                    default: code = 3;
+                }
+                switch (name) {
+                    case 'a': code = 4; break;
+                    case 'b':
+                    default: return 0;
+                }
+                switch (name) {
+                    default: code = 5;
+                    case 'c': code = 6; break;
+                    case 'd': return 7;
+                }
+                switch (code) { case 1: case 2: return code; }
+                while (code > 0) {
+                    switch (code) {
+                        case 1: if (name === 'x') break; code = 8;
+                        case 2: code = 9; break;
+                        case 3: continue;
+                    }
+                }
+                return code;
+            }
+            "#
+        )?;
+        assert_eq!(
+            rs.items[2],
+            syn::parse_str(
+                r#"
+                pub fn handle(event: LifecycleEvent, color: Color, code: f64, name: String) -> f64 {
+                    match event {
+                        LifecycleEvent::Load | LifecycleEvent::Domcontentloaded => { return 1.0; }
+                        LifecycleEvent::Networkidle => { code = 2.0; }
+                        _ => {}
+                    }
+                    match color {
+                        Color::Red => {}
+                        _ => { code = 3.0; }
+                    }
+                    match name.as_str() {
+                        "a" => { code = 4.0; }
+                        _ => { return 0.0; }
+                    }
+                    match name.as_str() {
+                        "c" => { code = 6.0; }
+                        "d" => { return 7.0; }
+                        _ => { code = 5.0; code = 6.0; }
+                    }
+                    match code {
+                        _ if code == 1.0 || code == 2.0 => { return code; }
+                        _ => {}
+                    }
+                    'looped: while code > 0.0 {
+                        match code {
+                            _ if code == 1.0 => {
+                                'case: {
+                                    if name == "x" { break 'case; }
+                                    code = 8.0;
+                                    code = 9.0;
+                                }
+                            }
+                            _ if code == 2.0 => { code = 9.0; }
+                            _ if code == 3.0 => { continue 'looped; }
+                            _ => {}
+                        }
+                    }
+                    return code;
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_leave_try_blocks() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export function compute(): number {
+                return 1;
+            }
+            export function first(items: string[]): number {
+                outer: for (const item of items) {
+                    try {
+                        if (item === 'skip') continue;
+                        while (item === 'stop') break outer;
+                        return compute();
+                    } catch (e) {
+                        return 0;
+                    } finally {
+                        done();
+                    }
+                }
+                return -1;
+            }
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                pub fn compute() -> f64 {
+                    return 1.0;
+                }
+                pub fn first(items: Vec<String>) -> f64 {
+                    'outer: for item in items {
+                        let flow = match (|| -> Result<Option<Flow<_>>, Error> {
+                            if item == "skip" {
+                                return Ok(Some(Flow::Continue(None)));
+                            }
+                            while item == "stop" {
+                                return Ok(Some(Flow::Break(Some("outer"))));
+                            }
+                            return Ok(Some(Flow::Return(compute())));
+                            Ok(None)
+                        })() {
+                            Ok(flow) => flow,
+                            Err(e) => {
+                                return 0.0;
+                            }
+                        };
+                        done();
+                        match flow {
+                            Some(Flow::Continue(None)) => {
+                                continue;
+                            }
+                            Some(Flow::Break(Some("outer"))) => {
+                                break 'outer;
+                            }
+                            Some(Flow::Return(value)) => {
+                                return value;
+                            }
+                            _ => {}
+                        }
+                    }
+                    return -1.0;
+                }
+                #[derive(Debug)]
+                pub enum Error {
+                    Message(String),
+                }
+                impl Error {
+                    pub fn new(message: impl Into<String>) -> Self {
+                        Error::Message(message.into())
+                    }
+                }
+                impl std::fmt::Display for Error {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        match self {
+                            Error::Message(message) => f.write_str(message),
+                        }
+                    }
+                }
+                impl std::error::Error for Error {}
+                #[derive(Debug)]
+                pub enum Flow<T> {
+                    Return(T),
+                    Break(Option<&'static str>),
+                    Continue(Option<&'static str>)
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_return_from_nested_try_blocks() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export function nested(ok: boolean): number {
+                try {
+                    try {
+                        if (!ok) throw new Error('not ok');
+                        return 1;
+                    } finally {
+                        inner();
+                    }
+                } finally {
+                    outer();
+                }
+                return 3;
+            }
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                pub fn nested(ok: bool) -> Result<f64, Error> {
+                    let result = (|| -> Result<Option<Flow<_>>, Error> {
+                        let result = (|| -> Result<Option<Flow<_>>, Error> {
+                            if !ok {
+                                return Err(Error::new("not ok"));
+                            }
+                            return Ok(Some(Flow::Return(1.0)));
+                            Ok(None)
+                        })();
+                        inner();
+                        let flow = result?;
+                        match flow {
+                            Some(Flow::Return(value)) => {
+                                return Ok(Some(Flow::Return(value)));
+                            }
+                            _ => {}
+                        }
+                        Ok(None)
+                    })();
+                    outer();
+                    let flow = result?;
+                    match flow {
+                        Some(Flow::Return(value)) => {
+                            return Ok(value);
+                        }
+                        _ => {}
+                    }
+                    return Ok(3.0);
+                }
+                #[derive(Debug)]
+                pub enum Error {
+                    Message(String),
+                }
+                impl Error {
+                    pub fn new(message: impl Into<String>) -> Self {
+                        Error::Message(message.into())
+                    }
+                }
+                impl std::fmt::Display for Error {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        match self {
+                            Error::Message(message) => f.write_str(message),
+                        }
+                    }
+                }
+                impl std::error::Error for Error {}
+                #[derive(Debug)]
+                pub enum Flow<T> {
+                    Return(T),
+                    Break(Option<&'static str>),
+                    Continue(Option<&'static str>)
+                }
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_exceptions() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export function check(ok: boolean): number {
+                if (!ok) {
+                    throw new Error('not ok');
+                }
+                return 1;
+            }
+            export function close(force: boolean) {
+                if (force) throw new Error('forced');
+            }
+            export async function run(page: Page) {
+                try {
+                    await page.close();
+                } catch (e) {
+                    log(e);
+                } finally {
+                    done();
+                }
+                try {
+                    check(false);
+                } catch {}
+                try {
+                    throw new Error('again');
+                } finally {
+                    done();
+                }
+            }
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                pub fn check(ok: bool) -> Result<f64, Error> {
+                    if !ok {
+                        return Err(Error::new("not ok"));
+                    }
+                    return Ok(1.0);
+                }
+                pub fn close(force: bool) -> Result<(), Error> {
+                    if force {
+                        return Err(Error::new("forced"));
+                    }
+                    Ok(())
+                }
+                pub async fn run(page: Page) -> Result<(), Error> {
+                    match async {
+                        page.close().await;
+                        Ok::<(), Error>(())
+                    }
+                    .await
+                    {
+                        Ok(()) => {}
+                        Err(e) => {
+                            log(e);
+                        }
+                    }
+                    done();
+                    match (|| -> Result<(), Error> {
+                        check(false)?;
+                        Ok(())
+                    })() {
+                        Ok(()) => {}
+                        Err(_) => {}
+                    }
+                    let result = (|| -> Result<(), Error> {
+                        return Err(Error::new("again"));
+                        Ok(())
+                    })();
+                    done();
+                    result?;
+                    Ok(())
+                }
+                #[derive(Debug)]
+                pub enum Error {
+                    Message(String),
+                }
+                impl Error {
+                    pub fn new(message: impl Into<String>) -> Self {
+                        Error::Message(message.into())
+                    }
+                }
+                impl std::fmt::Display for Error {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        match self {
+                            Error::Message(message) => f.write_str(message),
+                        }
+                    }
+                }
+                impl std::error::Error for Error {}
+                "#
+            )?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn can_convert_error_classes() -> anyhow::Result<()> {
+        let rs = convert_source(
+            r#"
+            export class TimeoutError extends Error {}
+            export class NavigationTimeoutError extends TimeoutError {}
+            export function wait(e: any, ms: number) {
+                if (e instanceof TimeoutError) {
+                    throw new NavigationTimeoutError(`waited ${ms}`);
+                }
+                throw new Error('failed');
+            }
+            "#
+        )?;
+        assert_eq!(
+            rs,
+            syn::parse_str(
+                r#"
+                pub fn wait(e: serde_json::Value, ms: f64) -> Result<(), Error> {
+                    if matches!(e, Error::TimeoutError(..) | Error::NavigationTimeoutError(..)) {
+                        return Err(Error::NavigationTimeoutError(format!("waited {}", ms).into()));
+                    }
+                    return Err(Error::new("failed"));
+                }
+                #[derive(Debug)]
+                pub enum Error {
+                    Message(String),
+                    TimeoutError(String),
+                    NavigationTimeoutError(String)
+                }
+                impl Error {
+                    pub fn new(message: impl Into<String>) -> Self {
+                        Error::Message(message.into())
+                    }
+                }
+                impl std::fmt::Display for Error {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        match self {
+                            Error::Message(message) => f.write_str(message),
+                            Error::TimeoutError(message) => write!(f, "TimeoutError: {}", message),
+                            Error::NavigationTimeoutError(message) =>
+                                write!(f, "NavigationTimeoutError: {}", message)
+                        }
+                    }
+                }
+                impl std::error::Error for Error {}
+                "#
+            )?
+        );
+        Ok(())
+    }
 }
@@ -9,7 +9,15 @@ use syn::__private::ToTokens;
 fn main() -> anyhow::Result<()> {
     let opt = Opt::from_args();
     let loaded = betterty::typescript::load(&opt.ts_file)?;
-    let rs = betterty::convert(loaded, &opt.dir)?;
+    let config = match &opt.config {
+        Some(p) => serde_json::from_reader(File::open(p)?)?,
+        None => betterty::config::Config::default()
+    };
+    let conversion = betterty::convert_with(loaded, &opt.dir, &config)?;
+    for choice in &conversion.numbers {
+        eprintln!("{}", choice);
+    }
+    let rs = conversion.files;
     dbg!(&rs);
     write(rs)?;
     Ok(())
@@ -18,7 +26,7 @@ fn main() -> anyhow::Result<()> {
 fn write(rs: Vec<(PathBuf, syn::File)>) -> anyhow::Result<()> {
     for (p, f) in rs.into_iter() {
         if let Some(d) = p.parent() {
-            fs::create_dir_all(&d)?;
+            fs::create_dir_all(d)?;
         }
         let mut file = File::open(&p)?;
         format(&mut file, f)?;
@@ -42,5 +50,8 @@ struct Opt {
     #[structopt(name = "TS", parse(from_os_str))]
     ts_file: PathBuf,
     #[structopt(name = "DIR", parse(from_os_str))]
-    dir: PathBuf
+    dir: PathBuf,
+    /// Json file of `betterty::config::Config`
+    #[structopt(long, parse(from_os_str))]
+    config: Option<PathBuf>
 }
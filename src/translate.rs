@@ -0,0 +1,581 @@
+mod case;
+mod class;
+mod error;
+mod expr;
+mod extends;
+mod number;
+mod overload;
+mod recursion;
+mod stmt;
+mod switch;
+mod traits;
+mod ty;
+mod utility;
+
+pub use number::{NumberChoice, NumberReason};
+pub use overload::overloaded_fns;
+pub use recursion::box_recursive;
+
+use crate::{config::Config, ident, ident_str, typescript::Parsed};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    convert::TryFrom
+};
+use swc_common::comments::SingleThreadedComments;
+use swc_ecma_ast as ast;
+use syn::parse_quote;
+use ty::{is_option, optional};
+
+/// Declarations visible to the translation of every module
+pub struct Context<'a> {
+    config: &'a Config,
+    interfaces: HashMap<&'a str, &'a ast::TsInterfaceDecl>,
+    classes: HashMap<&'a str, &'a ast::ClassDecl>,
+    types: HashMap<&'a str, syn::Type>,
+    integer: syn::Type,
+    numbers: HashMap<&'a str, syn::Type>,
+    comments: &'a SingleThreadedComments,
+    literals: HashMap<String, number::LiteralUse>,
+    number_choices: RefCell<Vec<NumberChoice>>,
+    generated: RefCell<Vec<syn::Item>>,
+    /// Values and variants of string literal unions and string enums by name
+    string_enums: HashMap<&'a str, Vec<(String, syn::Ident)>>,
+    /// Classes extending `Error`, which become variants of the generated `Error` enum
+    error_classes: Vec<&'a ast::ClassDecl>,
+    /// Functions returning a `Result`, whose calls propagate the error with `?`
+    throwing: HashSet<&'a str>,
+    /// Annotated types of the parameters and variables of the function being translated
+    bindings: RefCell<HashMap<String, ast::TsType>>,
+    /// Whether `return` in the body being translated produces a `Result`
+    returns_result: Cell<bool>,
+    /// Whether the body being translated returns floats
+    returns_float: Cell<bool>,
+    /// Loops, `switch` statements and labeled blocks enclosing the statement being translated
+    jumps: RefCell<Vec<stmt::Jump>>,
+    /// `try` blocks enclosing the statement being translated
+    tries: RefCell<Vec<error::Try>>,
+    /// Whether a translated body throws, catches or creates an `Error`, which needs the generated
+    /// `Error` enum
+    uses_error: Cell<bool>,
+    /// Whether a `try` block was left early, which needs the generated `Flow` enum
+    flows: Cell<bool>,
+    /// Path and field of the parent of the class being translated, which `super` refers to
+    super_class: RefCell<Option<(syn::Path, syn::Ident)>>,
+    /// Whether the method being translated is provided by the trait of an abstract class, which
+    /// reaches the fields of `this` through `base()`, and whether it takes `&mut self`
+    base: Cell<Option<bool>>,
+    /// Whether the expression being translated is assigned or mutably borrowed
+    assigned: Cell<bool>,
+    /// Whether the numeric literals of the expression being translated are floats, as its
+    /// target holds floats
+    float: Cell<bool>
+}
+
+impl<'a> Context<'a> {
+    pub fn new(
+        config: &'a Config,
+        root: &'a Parsed,
+        modules: impl IntoIterator<Item = &'a ast::Module>
+    ) -> anyhow::Result<Self> {
+        let mut interfaces = HashMap::new();
+        let mut classes = HashMap::new();
+        let mut string_enums = HashMap::new();
+        let mut class_order = Vec::new();
+        let mut functions = Vec::new();
+        for (_, decl) in std::iter::once(&root.ast).chain(modules).flat_map(decls) {
+            match decl {
+                ast::Decl::TsTypeAlias(a) => {
+                    if let Some(values) = string_literals(&a.type_ann) {
+                        let variants = variant_idents(&values);
+                        let variants = values.into_iter().zip(variants).collect();
+                        string_enums.entry(a.id.as_ref()).or_insert(variants);
+                    }
+                }
+                ast::Decl::TsEnum(e) => {
+                    if let Some(variants) = string_enum_members(e) {
+                        string_enums
+                            .entry(e.id.as_ref())
+                            .or_insert_with(|| variants.into_iter().map(|(i, v)| (v, i)).collect());
+                    }
+                }
+                ast::Decl::TsInterface(i) => {
+                    interfaces.entry(i.id.as_ref()).or_insert(i);
+                }
+                ast::Decl::Class(c) => {
+                    if !classes.contains_key(c.ident.as_ref()) {
+                        class_order.push(c.ident.as_ref());
+                    }
+                    classes.entry(c.ident.as_ref()).or_insert(c);
+                }
+                ast::Decl::Fn(f) => functions.push(f),
+                _ => {}
+            }
+        }
+        let mut types = HashMap::new();
+        for (ts, rs) in ty::BUILTIN_TYPES {
+            types.insert(*ts, syn::parse_str(rs)?);
+        }
+        for (ts, rs) in &config.types {
+            types.insert(&**ts, parse_type(ts, rs)?);
+        }
+        let numbers = config
+            .numbers
+            .iter()
+            .map(|(field, rs)| Ok((&**field, parse_type(field, rs)?)))
+            .collect::<anyhow::Result<_>>()?;
+        let error_classes = class_order
+            .into_iter()
+            .map(|name| classes[name])
+            .filter(|c| error::extends_error(&classes, &c.class))
+            .collect();
+        Ok(Self {
+            config,
+            interfaces,
+            classes,
+            types,
+            integer: parse_type("integer", &config.integer)?,
+            numbers,
+            comments: &root.comments,
+            literals: number::literal_uses(&root.ast),
+            number_choices: RefCell::new(Vec::new()),
+            generated: RefCell::new(Vec::new()),
+            string_enums,
+            error_classes,
+            throwing: error::throwing(&functions),
+            bindings: RefCell::new(HashMap::new()),
+            returns_result: Cell::new(false),
+            returns_float: Cell::new(false),
+            jumps: RefCell::new(Vec::new()),
+            tries: RefCell::new(Vec::new()),
+            uses_error: Cell::new(false),
+            flows: Cell::new(false),
+            super_class: RefCell::new(None),
+            base: Cell::new(None),
+            assigned: Cell::new(false),
+            float: Cell::new(false)
+        })
+    }
+
+    /// Interfaces with methods become traits and the others become structs
+    pub fn interface(&self, i: &ast::TsInterfaceDecl, vis: syn::Visibility) -> syn::Item {
+        if traits::is_trait(i) {
+            return syn::Item::Trait(self.interface_trait(i, vis));
+        }
+        let name = ident(&i.id);
+        let generics = self.generics(&i.type_params, true);
+        let fields = self.interface_fields(i);
+        syn::Item::Struct(parse_quote! {
+            #[derive(Debug, serde::Serialize, serde::Deserialize)]
+            #[serde(rename_all = "camelCase")]
+            #vis struct #name #generics {
+                #(#fields),*
+            }
+        })
+    }
+
+    fn fields(
+        &self,
+        owner: &str,
+        members: &[ast::TsTypeElement],
+        skip: Option<&str>
+    ) -> Vec<syn::Field> {
+        members
+            .iter()
+            .filter_map(|e| match e {
+                ast::TsTypeElement::TsPropertySignature(p) if !p.computed => {
+                    let key = prop_key(&p.key)?;
+                    if Some(&*key) == skip {
+                        return None;
+                    }
+                    let ty = self.field_type(owner, &key, p.span, &p.type_ann);
+                    Some(field(&key, if p.optional { optional(ty) } else { ty }))
+                }
+                _ => None
+            })
+            .collect()
+    }
+
+    pub fn type_alias(&self, a: &ast::TsTypeAliasDecl, vis: syn::Visibility) -> syn::Item {
+        let name = ident(&a.id);
+        if let Some(values) = string_literals(&a.type_ann) {
+            let variants: Vec<_> = variant_idents(&values).into_iter().zip(values).collect();
+            return syn::Item::Enum(string_enum(name, vis, &variants));
+        }
+        let generics = self.generics(&a.type_params, true);
+        if let Some(e) = self.tagged_union(&name, &vis, &generics, &a.type_ann) {
+            return syn::Item::Enum(e);
+        }
+        if let Some(s) = self.utility_struct(&name, &vis, &generics, &a.type_ann) {
+            return syn::Item::Struct(s);
+        }
+        if let ast::TsType::TsUnionOrIntersectionType(
+            ast::TsUnionOrIntersectionType::TsIntersectionType(i)
+        ) = &*a.type_ann
+        {
+            if let Some(fields) = self.intersection_fields(&a.id.sym, i) {
+                return parse_quote! {
+                    #[derive(Debug, serde::Serialize, serde::Deserialize)]
+                    #[serde(rename_all = "camelCase")]
+                    #vis struct #name #generics {
+                        #(#fields),*
+                    }
+                };
+            }
+        }
+        // Bounds on type aliases are not enforced, which rustc warns about
+        let mut generics = generics;
+        for p in generics.type_params_mut() {
+            p.colon_token = None;
+            p.bounds.clear();
+        }
+        let t = self.ty(&a.type_ann);
+        parse_quote!(#vis type #name #generics = #t;)
+    }
+
+    /// `{ type: 'a', x: number } | { type: 'b', y: string }` into an internally tagged enum
+    fn tagged_union(
+        &self,
+        name: &syn::Ident,
+        vis: &syn::Visibility,
+        generics: &syn::Generics,
+        t: &ast::TsType
+    ) -> Option<syn::ItemEnum> {
+        let u = match t {
+            ast::TsType::TsUnionOrIntersectionType(
+                ast::TsUnionOrIntersectionType::TsUnionType(u)
+            ) => u,
+            ast::TsType::TsParenthesizedType(p) => {
+                return self.tagged_union(name, vis, generics, &p.type_ann)
+            }
+            _ => return None
+        };
+        let members: Vec<&[ast::TsTypeElement]> = u
+            .types
+            .iter()
+            .map(|t| self.object_members(t))
+            .collect::<Option<_>>()?;
+        let tag = discriminant(&members)?;
+        let values: Vec<_> = members
+            .iter()
+            .map(|ms| string_literal_property(ms, &tag).unwrap())
+            .collect();
+        let idents = variant_idents(&values);
+        let variants =
+            members
+                .iter()
+                .zip(values)
+                .zip(idents)
+                .map(|((ms, value), variant)| -> syn::Variant {
+                    let owner = format!("{}.{}", name, variant);
+                    let fields = self
+                        .fields(&owner, ms, Some(&tag))
+                        .into_iter()
+                        .map(|mut f| {
+                            f.vis = syn::Visibility::Inherited;
+                            f
+                        });
+                    parse_quote! {
+                        #[serde(rename = #value, rename_all = "camelCase")]
+                        #variant {
+                            #(#fields),*
+                        }
+                    }
+                });
+        Some(parse_quote! {
+            #[derive(Debug, serde::Serialize, serde::Deserialize)]
+            #[serde(tag = #tag)]
+            #vis enum #name #generics {
+                #(#variants),*
+            }
+        })
+    }
+
+    /// Members of a type literal or of an interface declared in the loaded modules
+    fn object_members<'b>(&'b self, t: &'b ast::TsType) -> Option<&'b [ast::TsTypeElement]> {
+        match t {
+            ast::TsType::TsTypeLit(l) => Some(&l.members),
+            ast::TsType::TsParenthesizedType(p) => self.object_members(&p.type_ann),
+            ast::TsType::TsTypeRef(ast::TsTypeRef {
+                type_name: ast::TsEntityName::Ident(i),
+                type_params: None,
+                ..
+            }) => self.interfaces.get(i.as_ref()).map(|i| &i.body.body[..]),
+            _ => None
+        }
+    }
+
+    /// Numeric enums keep their discriminants, string enums are renamed to their values
+    ///
+    /// Discriminants that collide or overflow `i32` cannot be kept, so such enums are plain.
+    pub fn ts_enum(&self, e: &ast::TsEnumDecl, vis: syn::Visibility) -> syn::ItemEnum {
+        let name = ident(&e.id);
+        if let Some(variants) = string_enum_members(e) {
+            return string_enum(name, vis, &variants);
+        }
+        let mut next = Some(0);
+        let values: Vec<_> = e
+            .members
+            .iter()
+            .map(|m| {
+                let value = match &m.init {
+                    Some(init) => const_eval(init),
+                    None => next
+                };
+                next = value.map(|v| v + 1);
+                value
+            })
+            .collect();
+        let idents = enum_member_idents(e);
+        if !valid_discriminants(&values) {
+            return parse_quote! {
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+                #vis enum #name {
+                    #(#idents),*
+                }
+            };
+        }
+        let variants = idents
+            .into_iter()
+            .zip(values)
+            .map(|(variant, value)| -> syn::Variant {
+                match value {
+                    Some(v) => {
+                        let v = syn::LitInt::new(&v.to_string(), proc_macro2::Span::call_site());
+                        parse_quote!(#variant = #v)
+                    }
+                    None => parse_quote!(#variant)
+                }
+            });
+        parse_quote! {
+            #[derive(
+                Debug,
+                Clone,
+                Copy,
+                PartialEq,
+                Eq,
+                serde_repr::Serialize_repr,
+                serde_repr::Deserialize_repr
+            )]
+            #[repr(i32)]
+            #vis enum #name {
+                #(#variants),*
+            }
+        }
+    }
+}
+
+fn parse_type(name: &str, rs: &str) -> anyhow::Result<syn::Type> {
+    syn::parse_str(rs).map_err(|e| anyhow::anyhow!("invalid type `{}` for `{}`: {}", rs, name, e))
+}
+
+/// Declarations of a module with the visibility of their `export`
+pub fn decls(m: &ast::Module) -> impl Iterator<Item = (syn::Visibility, &ast::Decl)> {
+    m.body.iter().filter_map(|item| match item {
+        ast::ModuleItem::Stmt(ast::Stmt::Decl(d)) => Some((syn::Visibility::Inherited, d)),
+        ast::ModuleItem::ModuleDecl(ast::ModuleDecl::ExportDecl(e)) => {
+            Some((parse_quote!(pub), &e.decl))
+        }
+        _ => None
+    })
+}
+
+/// The property that every member declares with a distinct string literal type
+fn discriminant(members: &[&[ast::TsTypeElement]]) -> Option<String> {
+    let first = members.first()?;
+    first.iter().find_map(|e| {
+        let key = match e {
+            ast::TsTypeElement::TsPropertySignature(p) if !p.computed => prop_key(&p.key)?,
+            _ => return None
+        };
+        let values: Vec<_> = members
+            .iter()
+            .map(|ms| string_literal_property(ms, &key))
+            .collect::<Option<_>>()?;
+        let distinct = values
+            .iter()
+            .enumerate()
+            .all(|(i, v)| !values[..i].contains(v));
+        if distinct {
+            Some(key)
+        } else {
+            None
+        }
+    })
+}
+
+fn string_literal_property(members: &[ast::TsTypeElement], key: &str) -> Option<String> {
+    members.iter().find_map(|e| match e {
+        ast::TsTypeElement::TsPropertySignature(p)
+            if !p.computed && prop_key(&p.key).as_deref() == Some(key) =>
+        {
+            match p.type_ann.as_ref().map(|a| &*a.type_ann) {
+                Some(ast::TsType::TsLitType(ast::TsLitType {
+                    lit: ast::TsLit::Str(s),
+                    ..
+                })) => Some(s.value.to_string()),
+                _ => None
+            }
+        }
+        _ => None
+    })
+}
+
+/// `'a' | 'b'` into `["a", "b"]`
+fn string_literals(t: &ast::TsType) -> Option<Vec<String>> {
+    match t {
+        ast::TsType::TsUnionOrIntersectionType(ast::TsUnionOrIntersectionType::TsUnionType(u)) => u
+            .types
+            .iter()
+            .map(|t| match &**t {
+                ast::TsType::TsLitType(ast::TsLitType {
+                    lit: ast::TsLit::Str(s),
+                    ..
+                }) => Some(s.value.to_string()),
+                _ => None
+            })
+            .collect(),
+        ast::TsType::TsParenthesizedType(p) => string_literals(&p.type_ann),
+        _ => None
+    }
+}
+
+fn string_enum(
+    name: syn::Ident,
+    vis: syn::Visibility,
+    variants: &[(syn::Ident, String)]
+) -> syn::ItemEnum {
+    let variants = variants.iter().map(|(variant, v)| -> syn::Variant {
+        parse_quote! {
+            #[serde(rename = #v)]
+            #variant
+        }
+    });
+    parse_quote! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+        #vis enum #name {
+            #(#variants),*
+        }
+    }
+}
+
+fn enum_member_idents(e: &ast::TsEnumDecl) -> Vec<syn::Ident> {
+    let names: Vec<&str> = e
+        .members
+        .iter()
+        .map(|m| match &m.id {
+            ast::TsEnumMemberId::Ident(i) => i.as_ref(),
+            ast::TsEnumMemberId::Str(s) => &s.value
+        })
+        .collect();
+    variant_idents(&names)
+}
+
+/// Variants and values of an enum whose members are all initialized with strings
+fn string_enum_members(e: &ast::TsEnumDecl) -> Option<Vec<(syn::Ident, String)>> {
+    let strings: Option<Vec<_>> = e
+        .members
+        .iter()
+        .zip(enum_member_idents(e))
+        .map(|(m, variant)| match m.init.as_deref() {
+            Some(ast::Expr::Lit(ast::Lit::Str(s))) => Some((variant, s.value.to_string())),
+            _ => None
+        })
+        .collect();
+    strings.filter(|vs| !vs.is_empty())
+}
+
+/// Whether the discriminants fit `i32` and are distinct once the ones left out follow the
+/// previous variant as in Rust
+fn valid_discriminants(values: &[Option<i64>]) -> bool {
+    let mut seen = HashSet::new();
+    let mut prev = -1;
+    values.iter().all(|v| {
+        prev = v.unwrap_or(prev + 1);
+        i32::try_from(prev).is_ok() && seen.insert(prev)
+    })
+}
+
+/// Integer constant expressions allowed in enum initializers
+fn const_eval(e: &ast::Expr) -> Option<i64> {
+    match e {
+        ast::Expr::Lit(ast::Lit::Num(n)) if n.value.fract() == 0.0 => Some(n.value as i64),
+        ast::Expr::Paren(p) => const_eval(&p.expr),
+        ast::Expr::Unary(u) => {
+            let v = const_eval(&u.arg)?;
+            match u.op {
+                ast::UnaryOp::Minus => Some(-v),
+                ast::UnaryOp::Plus => Some(v),
+                ast::UnaryOp::Tilde => Some(!v),
+                _ => None
+            }
+        }
+        ast::Expr::Bin(b) => {
+            let (l, r) = (const_eval(&b.left)?, const_eval(&b.right)?);
+            match b.op {
+                ast::BinaryOp::Add => l.checked_add(r),
+                ast::BinaryOp::Sub => l.checked_sub(r),
+                ast::BinaryOp::Mul => l.checked_mul(r),
+                ast::BinaryOp::LShift => u32::try_from(r).ok().and_then(|r| l.checked_shl(r)),
+                ast::BinaryOp::RShift => u32::try_from(r).ok().and_then(|r| l.checked_shr(r)),
+                ast::BinaryOp::BitOr => Some(l | r),
+                ast::BinaryOp::BitAnd => Some(l & r),
+                ast::BinaryOp::BitXor => Some(l ^ r),
+                _ => None
+            }
+        }
+        _ => None
+    }
+}
+
+/// Pascal case variants, numbered from 2 when values differing only in case or punctuation
+/// would collide
+fn variant_idents(values: &[impl AsRef<str>]) -> Vec<syn::Ident> {
+    let mut used = HashSet::new();
+    values
+        .iter()
+        .map(|v| {
+            let pascal = case::pascal(v.as_ref());
+            let base = ident_str(if pascal.is_empty() { "Empty" } else { &pascal });
+            let mut variant = base.clone();
+            let mut n = 2;
+            while !used.insert(variant.to_string()) {
+                variant = ident_str(&format!("{}{}", base, n));
+                n += 1;
+            }
+            variant
+        })
+        .collect()
+}
+
+/// Snake case field that is renamed back to the original key when `rename_all` is not enough
+fn field(key: &str, ty: syn::Type) -> syn::Field {
+    let snake = case::snake(key);
+    let name = ident_str(&snake);
+    // Serde names the field after the identifier without `r#`, which may differ from the key
+    let unraw = name.to_string();
+    let unraw = unraw.trim_start_matches("r#");
+    let mut attrs = Vec::new();
+    if unraw != snake || case::camel(unraw) != key {
+        attrs.push(parse_quote!(#[serde(rename = #key)]));
+    }
+    if is_option(&ty) {
+        attrs.push(parse_quote!(#[serde(skip_serializing_if = "Option::is_none")]));
+    }
+    syn::Field {
+        attrs,
+        vis: parse_quote!(pub),
+        ident: Some(name),
+        colon_token: Some(Default::default()),
+        ty
+    }
+}
+
+fn prop_key(key: &ast::Expr) -> Option<String> {
+    match key {
+        ast::Expr::Ident(i) => Some(i.as_ref().to_owned()),
+        ast::Expr::Lit(ast::Lit::Str(s)) => Some(s.value.to_string()),
+        ast::Expr::Lit(ast::Lit::Num(n)) => Some(n.to_string()),
+        _ => None
+    }
+}
@@ -0,0 +1,68 @@
+/// Split `fooBar`, `FOO_BAR`, `foo-bar` and `innerHTML` into lowercase words
+fn words(s: &str) -> Vec<String> {
+    let cs: Vec<char> = s.chars().collect();
+    let mut words: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for (i, &c) in cs.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let prev = if i == 0 { None } else { cs.get(i - 1) };
+        let next = cs.get(i + 1);
+        let boundary = c.is_uppercase()
+            && match prev {
+                Some(p) if p.is_lowercase() || p.is_ascii_digit() => true,
+                Some(p) if p.is_uppercase() => next.map(|n| n.is_lowercase()).unwrap_or(false),
+                _ => false
+            };
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(w: &str) -> String {
+    let mut cs = w.chars();
+    match cs.next() {
+        Some(c) => c.to_uppercase().chain(cs).collect(),
+        None => String::new()
+    }
+}
+
+pub fn snake(s: &str) -> String { words(s).join("_") }
+
+pub fn camel(s: &str) -> String {
+    words(s)
+        .iter()
+        .enumerate()
+        .map(|(i, w)| if i == 0 { w.to_owned() } else { capitalize(w) })
+        .collect()
+}
+
+pub fn pascal(s: &str) -> String { words(s).iter().map(|w| capitalize(w)).collect() }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_change_case() {
+        assert_eq!(snake("fooBar"), "foo_bar");
+        assert_eq!(snake("innerHTML"), "inner_html");
+        assert_eq!(snake("HTMLElement"), "html_element");
+        assert_eq!(snake("x-y"), "x_y");
+        assert_eq!(snake("deviceScaleFactor2"), "device_scale_factor2");
+        assert_eq!(camel("foo_bar"), "fooBar");
+        assert_eq!(pascal("domcontentloaded"), "Domcontentloaded");
+        assert_eq!(pascal("no-preference"), "NoPreference");
+        assert_eq!(pascal("FOO_BAR"), "FooBar");
+    }
+}
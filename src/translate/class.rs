@@ -0,0 +1,837 @@
+use super::{
+    case,
+    expr::{holds_float, number},
+    overload::{signatures, Implementation, Overload, Overloaded},
+    prop_key,
+    traits::{overloaded_signatures, TraitMethods},
+    ty::{is_option, optional},
+    Context
+};
+use crate::{config::Callbacks, ident, ident_str};
+use std::collections::{HashMap, HashSet};
+use swc_common::DUMMY_SP;
+use swc_ecma_ast as ast;
+use swc_ecma_visit::{Node, Visit, VisitWith};
+use syn::parse_quote;
+
+impl Context<'_> {
+    /// Properties become fields and everything else goes to the inherent `impl`
+    pub fn class(&self, c: &ast::ClassDecl, vis: syn::Visibility) -> Vec<syn::Item> {
+        if self.is_error_class(c.ident.as_ref()) {
+            return Vec::new();
+        }
+        let name = if c.class.is_abstract {
+            base_ident(&c.ident)
+        } else {
+            ident(&c.ident)
+        };
+        let generics = self.generics(&c.class.type_params, true);
+        let (impl_generics, ty_generics, _) = generics.split_for_impl();
+        let parent = self.parent(&c.class);
+        let mut fields: Vec<syn::Field> = Vec::new();
+        if let Some(Parent { path, field, .. }) = &parent {
+            fields.push(syn::Field {
+                attrs: Vec::new(),
+                vis: parse_quote!(pub),
+                ident: Some(field.clone()),
+                colon_token: Some(Default::default()),
+                ty: syn::Type::Path(syn::TypePath {
+                    qself: None,
+                    path: path.clone()
+                })
+            });
+        }
+        let mut items: Vec<syn::ImplItem> = Vec::new();
+        let mut required: Vec<syn::TraitItem> = Vec::new();
+        *self.super_class.borrow_mut() = parent.as_ref().map(|p| (p.path.clone(), p.field.clone()));
+        let overloaded = overloaded_methods(&c.class);
+        let mut emitted = HashSet::new();
+        let mut args: Vec<syn::Item> = Vec::new();
+        let mut initializers: HashMap<syn::Ident, syn::Expr> = HashMap::new();
+        let mut ctor = None;
+        let ctors: Vec<_> = c
+            .class
+            .body
+            .iter()
+            .filter_map(|m| match m {
+                ast::ClassMember::Constructor(c) => Some(c),
+                _ => None
+            })
+            .collect();
+        let implemented = ctors.iter().any(|c| c.body.is_some());
+        for member in &c.class.body {
+            match member {
+                ast::ClassMember::ClassProp(p) if p.is_abstract => {
+                    if let Some(key) = prop_key(&p.key) {
+                        let name = ident_str(&case::snake(&key));
+                        let ty = self.type_ann_or_any(&p.type_ann);
+                        let ty = if p.is_optional { optional(ty) } else { ty };
+                        required.push(parse_quote!(fn #name(&self) -> #ty;));
+                    }
+                }
+                ast::ClassMember::ClassProp(p) if p.is_static => {
+                    if let Some(key) = prop_key(&p.key) {
+                        items.push(self.static_prop(&key, &p.type_ann, &p.value, &p.accessibility));
+                    }
+                }
+                ast::ClassMember::ClassProp(p) => {
+                    if let Some(key) = prop_key(&p.key) {
+                        let ty = self.field_type(&c.ident.sym, &key, p.span, &p.type_ann);
+                        let ty = if p.is_optional { optional(ty) } else { ty };
+                        let float = holds_float(&ty);
+                        let field = class_field(&key, ty, visibility(&p.accessibility));
+                        if let (Some(name), Some(value)) = (&field.ident, &p.value) {
+                            initializers.insert(name.clone(), self.typed_expr(value, float));
+                        }
+                        fields.push(field);
+                    }
+                }
+                ast::ClassMember::PrivateProp(p) if p.is_static => {
+                    items.push(self.static_prop(
+                        p.key.id.as_ref(),
+                        &p.type_ann,
+                        &p.value,
+                        &Some(ast::Accessibility::Private)
+                    ));
+                }
+                ast::ClassMember::PrivateProp(p) => {
+                    let ty = self.field_type(&c.ident.sym, p.key.id.as_ref(), p.span, &p.type_ann);
+                    let ty = if p.is_optional { optional(ty) } else { ty };
+                    let float = holds_float(&ty);
+                    let field = class_field(p.key.id.as_ref(), ty, syn::Visibility::Inherited);
+                    if let (Some(name), Some(value)) = (&field.ident, &p.value) {
+                        initializers.insert(name.clone(), self.typed_expr(value, float));
+                    }
+                    fields.push(field);
+                }
+                // Signatures are translated with the implementation of the constructor
+                ast::ClassMember::Constructor(k)
+                    if k.body.is_none() && (implemented || ctor.is_some()) => {}
+                ast::ClassMember::Constructor(k) => {
+                    let signatures = ctors.iter().copied().filter(|c| c.body.is_none());
+                    let (parts, props) = self.constructor(k, signatures.collect());
+                    fields.extend(props);
+                    ctor = Some((items.len(), parts));
+                }
+                ast::ClassMember::Method(m) => {
+                    if let Some(key) = prop_name(&m.key) {
+                        if let Some(fs) = overloaded.get(&(key.clone(), m.is_static)) {
+                            if emitted.insert((key.clone(), m.is_static)) {
+                                let vis = visibility(&m.accessibility);
+                                let overloads: Vec<_> =
+                                    fs.signatures.iter().map(|&f| Overload::from(f)).collect();
+                                // Overloads of a trait method take the enum of the trait
+                                let owner = self
+                                    .implemented_traits(&c.class)
+                                    .find(|(_, i)| overloaded_signatures(i).contains_key(&key))
+                                    .map(|(_, i)| i.id.as_ref())
+                                    .filter(|_| !m.is_static);
+                                let mutates = fs.implementation.map(mutates_this).unwrap_or(false);
+                                self.base.set(if c.class.is_abstract && !m.is_static {
+                                    Some(mutates)
+                                } else {
+                                    None
+                                });
+                                let implementation = fs.implementation.map(Implementation::from);
+                                let (fns, e) = self.overloads(
+                                    owner.unwrap_or_else(|| c.ident.as_ref()),
+                                    &key,
+                                    &vis,
+                                    &overloads,
+                                    implementation.as_ref()
+                                );
+                                self.base.set(None);
+                                if owner.is_none() {
+                                    args.extend(e);
+                                }
+                                for (mut sig, body) in fns {
+                                    if !m.is_static {
+                                        let receiver: syn::FnArg = if mutates {
+                                            parse_quote!(&mut self)
+                                        } else {
+                                            parse_quote!(&self)
+                                        };
+                                        sig.inputs.insert(0, receiver);
+                                    }
+                                    let body = body.unwrap_or_else(|| parse_quote!({}));
+                                    items.push(parse_quote!(#vis #sig #body));
+                                }
+                            }
+                            continue;
+                        }
+                        let method = self.method(
+                            &key,
+                            &m.function,
+                            m.kind,
+                            m.is_static,
+                            c.class.is_abstract,
+                            visibility(&m.accessibility)
+                        );
+                        match method {
+                            syn::ImplItem::Method(method) if m.is_abstract => {
+                                let sig = method.sig;
+                                required.push(parse_quote!(#sig;));
+                            }
+                            method => items.push(method)
+                        }
+                    }
+                }
+                ast::ClassMember::PrivateMethod(m) => {
+                    items.push(self.method(
+                        m.key.id.as_ref(),
+                        &m.function,
+                        m.kind,
+                        m.is_static,
+                        c.class.is_abstract,
+                        syn::Visibility::Inherited
+                    ));
+                }
+                ast::ClassMember::TsIndexSignature(_) | ast::ClassMember::Empty(_) => {}
+            }
+        }
+        if let Some((i, ctor)) = ctor {
+            let value = construct(&fields, &ctor.initialized, initializers);
+            let (news, e) = self.news(&c.ident, &ctor, value);
+            args.extend(e);
+            items.splice(i..i, news);
+        }
+        *self.super_class.borrow_mut() = None;
+        let mut impls: Vec<syn::Item> = Vec::new();
+        if c.class.is_abstract {
+            let t = ident(&c.ident);
+            let (provided, rest) = items.into_iter().partition(|item| match item {
+                syn::ImplItem::Method(m) => m.sig.receiver().is_some(),
+                _ => false
+            });
+            items = rest;
+            let provided = provided
+                .into_iter()
+                .filter_map(|item| -> Option<syn::TraitItem> {
+                    match item {
+                        syn::ImplItem::Method(m) => {
+                            let (attrs, sig, block) = (m.attrs, m.sig, m.block);
+                            Some(parse_quote!(#(#attrs)* #sig #block))
+                        }
+                        _ => None
+                    }
+                });
+            impls.push(parse_quote! {
+                #vis trait #t #generics {
+                    fn base(&self) -> &#name #ty_generics;
+                    fn base_mut(&mut self) -> &mut #name #ty_generics;
+                    #(#required)*
+                    #(#provided)*
+                }
+            });
+        } else {
+            let mut traits: Vec<_> = self
+                .implemented_traits(&c.class)
+                .map(|(t, i)| (t, self.trait_methods(i), Vec::new()))
+                .collect();
+            if let Some(Parent {
+                path,
+                field,
+                abstract_class: Some(a)
+            }) = &parent
+            {
+                let mut t = path.clone();
+                if let Some(last) = t.segments.last_mut() {
+                    last.ident = ident(&a.ident);
+                }
+                let accessors: Vec<syn::ImplItem> = vec![
+                    parse_quote!(fn base(&self) -> &#path { &self.#field }),
+                    parse_quote!(fn base_mut(&mut self) -> &mut #path { &mut self.#field }),
+                ];
+                let methods = TraitMethods {
+                    names: abstract_method_names(&a.class),
+                    mutable: HashSet::new(),
+                    getters: Vec::new()
+                };
+                traits.push((t, methods, accessors));
+            }
+            for (t, trait_methods, accessors) in traits {
+                let (moved, rest): (Vec<_>, _) = items.into_iter().partition(|item| match item {
+                    syn::ImplItem::Method(m) => trait_methods.names.contains(&m.sig.ident),
+                    _ => false
+                });
+                items = rest;
+                // Fields provide the getters of properties no method implements
+                let getters: Vec<syn::ImplItem> = trait_methods
+                    .getters
+                    .iter()
+                    .filter(|(name, _)| {
+                        !moved.iter().any(|item| match item {
+                            syn::ImplItem::Method(m) => &m.sig.ident == name,
+                            _ => false
+                        })
+                    })
+                    .filter(|(name, _)| fields.iter().any(|f| f.ident.as_ref() == Some(name)))
+                    .map(|(name, ty)| {
+                        let value: syn::Expr = if is_copy(ty) {
+                            parse_quote!(self.#name)
+                        } else {
+                            parse_quote!(self.#name.clone())
+                        };
+                        parse_quote!(fn #name(&self) -> #ty { #value })
+                    })
+                    .collect();
+                let methods = accessors
+                    .into_iter()
+                    .chain(getters)
+                    .chain(moved.into_iter().map(|mut item: syn::ImplItem| {
+                        if let syn::ImplItem::Method(m) = &mut item {
+                            m.vis = syn::Visibility::Inherited;
+                            // The receiver of the trait, which another implementation may need
+                            // to be mutable
+                            if trait_methods.mutable.contains(&m.sig.ident) {
+                                if let Some(syn::FnArg::Receiver(r)) = m.sig.inputs.first_mut() {
+                                    r.mutability = Some(Default::default());
+                                }
+                            }
+                        }
+                        item
+                    }));
+                impls.push(parse_quote! {
+                    impl #impl_generics #t for #name #ty_generics {
+                        #(#methods)*
+                    }
+                });
+            }
+        }
+        let mut res = vec![syn::Item::Struct(parse_quote! {
+            #vis struct #name #generics {
+                #(#fields),*
+            }
+        })];
+        res.extend(args);
+        if !items.is_empty() {
+            res.push(syn::Item::Impl(parse_quote! {
+                impl #impl_generics #name #ty_generics {
+                    #(#items)*
+                }
+            }));
+        }
+        res.extend(impls);
+        if let Some(Parent { path, field, .. }) = &parent {
+            if self.config.deref_parent {
+                res.push(parse_quote! {
+                    impl #impl_generics std::ops::Deref for #name #ty_generics {
+                        type Target = #path;
+                        fn deref(&self) -> &#path { &self.#field }
+                    }
+                });
+                res.push(parse_quote! {
+                    impl #impl_generics std::ops::DerefMut for #name #ty_generics {
+                        fn deref_mut(&mut self) -> &mut #path { &mut self.#field }
+                    }
+                });
+            }
+            if self.config.as_ref_parent {
+                res.push(parse_quote! {
+                    impl #impl_generics AsRef<#path> for #name #ty_generics {
+                        fn as_ref(&self) -> &#path { &self.#field }
+                    }
+                });
+            }
+        }
+        res
+    }
+
+    /// Path of a class, where built-in classes like `Map` are mapped through the type table
+    pub(super) fn class_path(&self, e: &ast::Expr) -> Option<syn::Path> {
+        if let ast::Expr::Ident(i) = e {
+            match self.types.get(i.as_ref()) {
+                Some(syn::Type::Path(p))
+                    if p.qself.is_none() && !self.classes.contains_key(i.as_ref()) =>
+                {
+                    return Some(p.path.clone());
+                }
+                _ => {}
+            }
+        }
+        expr_path(e)
+    }
+
+    /// Type of `extends` and the field embedding it, through which `super` is reached
+    pub(super) fn parent(&self, c: &ast::Class) -> Option<Parent<'_>> {
+        let mut path = self.class_path(c.super_class.as_deref()?)?;
+        let last = path.segments.last_mut()?;
+        let abstract_class = self
+            .classes
+            .get(&*last.ident.to_string())
+            .copied()
+            .filter(|a| a.class.is_abstract);
+        if let Some(a) = abstract_class {
+            last.ident = base_ident(&a.ident);
+        }
+        let field = ident_str(&case::snake(&last.ident.to_string()));
+        if let Some(args) = &c.super_type_params {
+            let args = args.params.iter().map(|t| self.ty(t));
+            last.arguments = syn::PathArguments::AngleBracketed(parse_quote!(<#(#args),*>));
+        }
+        Some(Parent {
+            path,
+            field,
+            abstract_class
+        })
+    }
+
+    /// `constructor` prepared for `fn new`, and its parameter properties into fields
+    ///
+    /// `this.f = e` and `super(..)` become locals named after the fields they initialize.
+    fn constructor<'c>(
+        &self,
+        ctor: &ast::Constructor,
+        signatures: Vec<&'c ast::Constructor>
+    ) -> (Constructor<'c>, Vec<syn::Field>) {
+        let mut fields = Vec::new();
+        let pats: Vec<ast::Pat> = ctor
+            .params
+            .iter()
+            .map(|p| {
+                let pat = param_pat(p);
+                if let ast::ParamOrTsParamProp::TsParamProp(p) = p {
+                    // Fields cannot hold `impl Fn`
+                    if let Some(syn::FnArg::Typed(t)) = self.fn_arg(0, &pat, Callbacks::Boxed) {
+                        if let syn::Pat::Ident(i) = &*t.pat {
+                            fields.push(syn::Field {
+                                attrs: Vec::new(),
+                                vis: visibility(&p.accessibility),
+                                ident: Some(ident_str(&case::snake(&i.ident.to_string()))),
+                                colon_token: Some(Default::default()),
+                                ty: *t.ty
+                            });
+                        }
+                    }
+                }
+                pat
+            })
+            .collect();
+        let mut initialized: Vec<syn::Ident> =
+            fields.iter().filter_map(|f| f.ident.clone()).collect();
+        let parent = self
+            .super_class
+            .borrow()
+            .as_ref()
+            .map(|(_, f)| f.to_string());
+        let body = ctor.body.as_ref().map(|b| {
+            let mut b = b.clone();
+            for s in &mut b.stmts {
+                let local = match s {
+                    ast::Stmt::Expr(e) => match &*e.expr {
+                        ast::Expr::Assign(a) if a.op == ast::AssignOp::Assign => {
+                            this_field(&a.left).map(|f| (case::snake(&f), a.right.clone()))
+                        }
+                        ast::Expr::Call(ast::CallExpr {
+                            callee: ast::ExprOrSuper::Super(_),
+                            ..
+                        }) => parent.clone().map(|f| (f, e.expr.clone())),
+                        _ => None
+                    },
+                    _ => None
+                };
+                if let Some((name, init)) = local {
+                    initialized.push(ident_str(&name));
+                    *s = ast::Stmt::Decl(ast::Decl::Var(local_decl(&name, init)));
+                }
+            }
+            b
+        });
+        let ctor = Constructor {
+            vis: visibility(&ctor.accessibility),
+            pats,
+            initialized,
+            body,
+            signatures
+        };
+        (ctor, fields)
+    }
+
+    /// `fn new` ending with the constructed value, or one per overload signature of the
+    /// constructor, with the enum of their arguments if any
+    fn news(
+        &self,
+        class: &ast::Ident,
+        ctor: &Constructor<'_>,
+        value: syn::Expr
+    ) -> (Vec<syn::ImplItem>, Option<syn::Item>) {
+        let vis = &ctor.vis;
+        let signatures = &ctor.signatures;
+        if signatures.len() < 2 && (signatures.is_empty() || ctor.body.is_none()) {
+            let (generics, inputs) = self.fn_params(&None, &ctor.pats);
+            let mut output = parse_quote!(-> Self);
+            let mut body = self.body(&ctor.pats, ctor.body.as_ref(), &mut output);
+            body.stmts
+                .push(syn::Stmt::Expr(if output == parse_quote!(-> Self) {
+                    value
+                } else {
+                    parse_quote!(Ok(#value))
+                }));
+            let new = parse_quote!(#vis fn new #generics(#inputs) #output #body);
+            return (vec![new], None);
+        }
+        let overloads: Vec<_> = signatures
+            .iter()
+            .map(|c| Overload {
+                params: c.params.iter().map(param_pat).collect(),
+                type_params: &None,
+                return_type: &None,
+                is_async: false,
+                constructs: true
+            })
+            .collect();
+        let implementation = ctor.body.as_ref().map(|b| Implementation {
+            params: ctor.pats.clone(),
+            body: Some(b),
+            value: Some(value)
+        });
+        let (fns, args) = self.overloads(
+            class.as_ref(),
+            "new",
+            vis,
+            &overloads,
+            implementation.as_ref()
+        );
+        let news = fns
+            .into_iter()
+            .map(|(sig, body)| {
+                let body = body.unwrap_or_else(|| parse_quote!({}));
+                parse_quote!(#vis #sig #body)
+            })
+            .collect();
+        (news, args)
+    }
+
+    /// The methods of an abstract class are provided by its trait and reach its fields through
+    /// `base()`.
+    fn method(
+        &self,
+        key: &str,
+        f: &ast::Function,
+        kind: ast::MethodKind,
+        is_static: bool,
+        is_abstract: bool,
+        vis: syn::Visibility
+    ) -> syn::ImplItem {
+        let name = method_name(key, kind);
+        let (generics, mut inputs) =
+            self.fn_params(&f.type_params, f.params.iter().map(|p| &p.pat));
+        let mutates = kind == ast::MethodKind::Setter || mutates_this(f);
+        if !is_static {
+            let receiver: syn::FnArg = if mutates {
+                parse_quote!(&mut self)
+            } else {
+                parse_quote!(&self)
+            };
+            inputs.insert(0, receiver);
+        }
+        let (asyncness, mut output) = self.async_return_type(&f.return_type, f.is_async);
+        self.base.set(if is_abstract && !is_static {
+            Some(mutates)
+        } else {
+            None
+        });
+        let body = self.body(
+            f.params.iter().map(|p| &p.pat),
+            f.body.as_ref(),
+            &mut output
+        );
+        self.base.set(None);
+        parse_quote!(#vis #asyncness fn #name #generics(#inputs) #output #body)
+    }
+
+    /// Literal initializers become associated consts and the others associated functions
+    fn static_prop(
+        &self,
+        key: &str,
+        type_ann: &Option<ast::TsTypeAnn>,
+        value: &Option<Box<ast::Expr>>,
+        accessibility: &Option<ast::Accessibility>
+    ) -> syn::ImplItem {
+        let vis = visibility(accessibility);
+        let name = ident_str(&case::snake(key).to_uppercase());
+        let (ty, value): (syn::Type, syn::Expr) = match value.as_deref() {
+            Some(ast::Expr::Lit(ast::Lit::Str(s))) => {
+                let s = &*s.value;
+                (parse_quote!(&'static str), parse_quote!(#s))
+            }
+            Some(ast::Expr::Lit(ast::Lit::Bool(b))) => {
+                let b = b.value;
+                (parse_quote!(bool), parse_quote!(#b))
+            }
+            Some(ast::Expr::Lit(ast::Lit::Num(n))) => {
+                let ty = self.types["number"].clone();
+                let float = holds_float(&ty);
+                (ty, number(n.value, float))
+            }
+            // Other initializers are evaluated at each access
+            value => {
+                let name = ident_str(&case::snake(key));
+                let ty = self.type_ann_or_any(type_ann);
+                let value: syn::Expr = match value {
+                    Some(value) => self.expr(value),
+                    None => parse_quote!(todo!())
+                };
+                return parse_quote!(#vis fn #name() -> #ty { #value });
+            }
+        };
+        parse_quote!(#vis const #name: #ty = #value;)
+    }
+}
+
+/// `Self { .. }` from the locals initializing fields, the initializers of the fields and `None`
+/// for optional fields
+fn construct(
+    fields: &[syn::Field],
+    initialized: &[syn::Ident],
+    mut initializers: HashMap<syn::Ident, syn::Expr>
+) -> syn::Expr {
+    let values = fields.iter().filter_map(|f| {
+        let name = f.ident.as_ref()?;
+        Some(if initialized.contains(name) {
+            field_value(name, None)
+        } else if let Some(value) = initializers.remove(name) {
+            field_value(name, Some(value))
+        } else if is_option(&f.ty) {
+            field_value(name, Some(parse_quote!(None)))
+        } else {
+            field_value(name, Some(parse_quote!(Default::default())))
+        })
+    });
+    parse_quote!(Self { #(#values),* })
+}
+
+fn field_value(name: &syn::Ident, value: Option<syn::Expr>) -> syn::FieldValue {
+    match value {
+        Some(value) => parse_quote!(#name: #value),
+        None => parse_quote!(#name)
+    }
+}
+
+/// Field of `this.f` or `this.#f`
+fn this_field(e: &ast::PatOrExpr) -> Option<String> {
+    let e = match e {
+        ast::PatOrExpr::Expr(e) => &**e,
+        ast::PatOrExpr::Pat(p) => match &**p {
+            ast::Pat::Expr(e) => &**e,
+            _ => return None
+        }
+    };
+    match e {
+        ast::Expr::Member(ast::MemberExpr {
+            obj: ast::ExprOrSuper::Expr(obj),
+            prop,
+            computed: false,
+            ..
+        }) if matches!(&**obj, ast::Expr::This(_)) => match &**prop {
+            ast::Expr::PrivateName(p) => Some(p.id.as_ref().to_owned()),
+            prop => prop_key(prop)
+        },
+        _ => None
+    }
+}
+
+/// `const name = init`
+fn local_decl(name: &str, init: Box<ast::Expr>) -> ast::VarDecl {
+    ast::VarDecl {
+        span: DUMMY_SP,
+        kind: ast::VarDeclKind::Const,
+        declare: false,
+        decls: vec![ast::VarDeclarator {
+            span: DUMMY_SP,
+            name: ast::Pat::Ident(ast::BindingIdent {
+                id: ast::Ident::new(name.into(), DUMMY_SP),
+                type_ann: None
+            }),
+            init: Some(init),
+            definite: false
+        }]
+    }
+}
+
+pub(super) struct Parent<'a> {
+    pub path: syn::Path,
+    pub field: syn::Ident,
+    /// Abstract classes are split into a trait and the `Base` struct that `path` points to
+    pub abstract_class: Option<&'a ast::ClassDecl>
+}
+
+/// Methods declared with several signatures, keyed by name and staticness
+fn overloaded_methods(c: &ast::Class) -> HashMap<(String, bool), Overloaded<'_>> {
+    let mut methods: HashMap<(String, bool), Vec<&ast::Function>> = HashMap::new();
+    for m in &c.body {
+        if let ast::ClassMember::Method(m) = m {
+            if m.kind == ast::MethodKind::Method && !m.is_abstract {
+                if let Some(key) = prop_name(&m.key) {
+                    methods
+                        .entry((key, m.is_static))
+                        .or_default()
+                        .push(&m.function);
+                }
+            }
+        }
+    }
+    methods
+        .into_iter()
+        .filter_map(|(key, fs)| Some((key, signatures(fs)?)))
+        .collect()
+}
+
+/// Constructor waiting for the fields of its class
+struct Constructor<'c> {
+    vis: syn::Visibility,
+    pats: Vec<ast::Pat>,
+    /// Fields assigned by the body or by parameter properties
+    initialized: Vec<syn::Ident>,
+    body: Option<ast::BlockStmt>,
+    /// Overload signatures declared before the implementation
+    signatures: Vec<&'c ast::Constructor>
+}
+
+/// Whether a type is a primitive that getters return by copy
+fn is_copy(ty: &syn::Type) -> bool {
+    const COPY: &[&str] = &[
+        "bool", "char", "f32", "f64", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16",
+        "u32", "u64", "u128", "usize"
+    ];
+    match ty {
+        syn::Type::Path(p) => p
+            .path
+            .get_ident()
+            .map(|i| COPY.iter().any(|c| i == c))
+            .unwrap_or(false),
+        _ => false
+    }
+}
+
+/// Pattern of a parameter or parameter property
+fn param_pat(p: &ast::ParamOrTsParamProp) -> ast::Pat {
+    match p {
+        ast::ParamOrTsParamProp::Param(p) => p.pat.clone(),
+        ast::ParamOrTsParamProp::TsParamProp(p) => match &p.param {
+            ast::TsParamPropParam::Ident(b) => ast::Pat::Ident(b.clone()),
+            ast::TsParamPropParam::Assign(a) => ast::Pat::Assign(a.clone())
+        }
+    }
+}
+
+/// Struct holding the fields of an abstract class
+fn base_ident(class: &ast::Ident) -> syn::Ident { ident_str(&format!("{}Base", class.as_ref())) }
+
+fn method_name(key: &str, kind: ast::MethodKind) -> syn::Ident {
+    let snake = case::snake(key);
+    match kind {
+        ast::MethodKind::Setter => ident_str(&format!("set_{}", snake)),
+        _ => ident_str(&snake)
+    }
+}
+
+/// Names of the trait methods of an abstract class that derived classes may override
+fn abstract_method_names(c: &ast::Class) -> Vec<syn::Ident> {
+    c.body
+        .iter()
+        .filter_map(|m| match m {
+            ast::ClassMember::Method(m) if !m.is_static => {
+                prop_name(&m.key).map(|k| method_name(&k, m.kind))
+            }
+            ast::ClassMember::ClassProp(p) if p.is_abstract => {
+                prop_key(&p.key).map(|k| ident_str(&case::snake(&k)))
+            }
+            _ => None
+        })
+        .collect()
+}
+
+/// `a.b.C` into `a::b::C`
+pub(super) fn expr_path(e: &ast::Expr) -> Option<syn::Path> {
+    match e {
+        ast::Expr::Ident(i) => {
+            let i = ident(i);
+            Some(parse_quote!(#i))
+        }
+        ast::Expr::Member(m) if !m.computed => {
+            let obj = match &m.obj {
+                ast::ExprOrSuper::Expr(o) => expr_path(o)?,
+                ast::ExprOrSuper::Super(_) => return None
+            };
+            let prop = match &*m.prop {
+                ast::Expr::Ident(i) => ident(i),
+                _ => return None
+            };
+            Some(parse_quote!(#obj::#prop))
+        }
+        ast::Expr::Paren(p) => expr_path(&p.expr),
+        _ => None
+    }
+}
+
+fn class_field(key: &str, ty: syn::Type, vis: syn::Visibility) -> syn::Field {
+    syn::Field {
+        attrs: Vec::new(),
+        vis,
+        ident: Some(ident_str(&case::snake(key))),
+        colon_token: Some(Default::default()),
+        ty
+    }
+}
+
+fn visibility(accessibility: &Option<ast::Accessibility>) -> syn::Visibility {
+    match accessibility {
+        Some(ast::Accessibility::Private) => syn::Visibility::Inherited,
+        Some(ast::Accessibility::Protected) => parse_quote!(pub(crate)),
+        Some(ast::Accessibility::Public) | None => parse_quote!(pub)
+    }
+}
+
+pub(super) fn prop_name(key: &ast::PropName) -> Option<String> {
+    match key {
+        ast::PropName::Ident(i) => Some(i.as_ref().to_owned()),
+        ast::PropName::Str(s) => Some(s.value.to_string()),
+        ast::PropName::Num(n) => Some(n.to_string()),
+        ast::PropName::Computed(_) | ast::PropName::BigInt(_) => None
+    }
+}
+
+/// Whether the body assigns to a property of `this`
+pub(super) fn mutates_this(f: &ast::Function) -> bool {
+    let mut v = ThisMutation(false);
+    if let Some(body) = &f.body {
+        body.visit_children_with(&mut v);
+    }
+    v.0
+}
+
+struct ThisMutation(bool);
+
+impl Visit for ThisMutation {
+    fn visit_assign_expr(&mut self, n: &ast::AssignExpr, _parent: &dyn Node) {
+        let target = match &n.left {
+            ast::PatOrExpr::Expr(e) => Some(&**e),
+            ast::PatOrExpr::Pat(p) => match &**p {
+                ast::Pat::Expr(e) => Some(&**e),
+                _ => None
+            }
+        };
+        self.0 |= target.map(is_this_member).unwrap_or(false);
+        n.visit_children_with(self);
+    }
+
+    fn visit_update_expr(&mut self, n: &ast::UpdateExpr, _parent: &dyn Node) {
+        self.0 |= is_this_member(&n.arg);
+        n.visit_children_with(self);
+    }
+
+    // `this` of a nested function is not the instance
+    fn visit_function(&mut self, _n: &ast::Function, _parent: &dyn Node) {}
+}
+
+fn is_this_member(e: &ast::Expr) -> bool {
+    match e {
+        ast::Expr::Member(m) => match &m.obj {
+            ast::ExprOrSuper::Expr(o) => matches!(&**o, ast::Expr::This(_)) || is_this_member(o),
+            ast::ExprOrSuper::Super(_) => false
+        },
+        ast::Expr::Paren(p) => is_this_member(&p.expr),
+        _ => false
+    }
+}
@@ -0,0 +1,395 @@
+use super::{
+    expr::{paren, POSTFIX},
+    Context
+};
+use crate::ident;
+use std::collections::{HashMap, HashSet};
+use swc_ecma_ast as ast;
+use swc_ecma_visit::{Node, Visit, VisitWith};
+use syn::parse_quote;
+
+/// `try` block enclosing the statement being translated
+pub(super) struct Try {
+    /// Number of jumps enclosing the `try`, which `break` and `continue` leave when they target
+    /// one of them
+    depth: usize,
+    exits: Vec<Exit>
+}
+
+/// Way out of a `try` block before its end, taken again after `catch` and `finally`
+#[derive(Clone, PartialEq)]
+pub(super) enum Exit {
+    Return,
+    Break(Option<String>),
+    Continue(Option<String>)
+}
+
+impl Context<'_> {
+    /// `throw e` returns `Err(e)` from the function or the closure of the enclosing `try`
+    pub(super) fn throw(&self, t: &ast::ThrowStmt) -> syn::Stmt {
+        let e = self.expr(&t.arg);
+        parse_quote!(return Err(#e);)
+    }
+
+    /// `return` wraps its value in `Ok` where the body was turned into a `Result`
+    pub(super) fn return_stmt(&self, arg: Option<&ast::Expr>) -> syn::Stmt {
+        let e: Option<syn::Expr> = arg.map(|e| self.typed_expr(e, self.returns_float.get()));
+        self.leave_with(e)
+    }
+
+    /// Returns from the closure of the innermost `try` when inside one, else from the function
+    fn leave_with(&self, e: Option<syn::Expr>) -> syn::Stmt {
+        if self.leave_try(None, Exit::Return) {
+            let e = e.unwrap_or_else(|| parse_quote!(()));
+            return parse_quote!(return Ok(Some(Flow::Return(#e))););
+        }
+        self.return_value(e)
+    }
+
+    fn return_value(&self, e: Option<syn::Expr>) -> syn::Stmt {
+        match (e, self.returns_result.get()) {
+            (Some(e), true) => parse_quote!(return Ok(#e);),
+            (None, true) => parse_quote!(return Ok(());),
+            (Some(e), false) => parse_quote!(return #e;),
+            (None, false) => parse_quote!(return;)
+        }
+    }
+
+    /// Records a way out of the innermost `try` when it leaves it
+    ///
+    /// `target` is the position of the jump targeted by `break` or `continue`, or `None` for
+    /// `return`.
+    pub(super) fn leave_try(&self, target: Option<usize>, exit: Exit) -> bool {
+        let mut tries = self.tries.borrow_mut();
+        match tries.last_mut() {
+            Some(t) if target.map(|i| i < t.depth).unwrap_or(true) => {
+                if !t.exits.contains(&exit) {
+                    t.exits.push(exit);
+                }
+                true
+            }
+            _ => false
+        }
+    }
+
+    /// `try` evaluates its block into a `Result` that `catch` matches, followed by `finally`
+    ///
+    /// The block is a closure, or an `async` block awaited when it awaits. Without `catch` the
+    /// error is propagated after `finally` runs. A `return`, `break` or `continue` leaving the
+    /// block returns a `Flow` from it that is taken again at the end.
+    pub(super) fn try_stmt(&self, t: &ast::TryStmt) -> Vec<syn::Stmt> {
+        self.uses_error.set(true);
+        self.tries.borrow_mut().push(Try {
+            depth: self.jumps.borrow().len(),
+            exits: Vec::new()
+        });
+        let stmts = self.stmts(&t.block.stmts);
+        let exits = self.tries.borrow_mut().pop().expect("pushed above").exits;
+        let (ok, end): (syn::Type, syn::Expr) = if exits.is_empty() {
+            (parse_quote!(()), parse_quote!(()))
+        } else if exits.contains(&Exit::Return) {
+            (parse_quote!(Option<Flow<_>>), parse_quote!(None))
+        } else {
+            (parse_quote!(Option<Flow<()>>), parse_quote!(None))
+        };
+        let block: syn::Expr = if awaits(&t.block) {
+            parse_quote!(async { #(#stmts)* Ok::<#ok, Error>(#end) }.await)
+        } else {
+            parse_quote!((|| -> Result<#ok, Error> { #(#stmts)* Ok(#end) })())
+        };
+        let finalizer = t
+            .finalizer
+            .as_ref()
+            .map(|f| self.stmts(&f.stmts))
+            .unwrap_or_default();
+        let mut res: Vec<syn::Stmt> = Vec::new();
+        match &t.handler {
+            Some(h) => {
+                let pat: syn::Pat = match &h.param {
+                    Some(p) => self.pat(p, false).0,
+                    None => parse_quote!(_)
+                };
+                let handler = self.stmts(&h.body.stmts);
+                let diverges = matches!(
+                    h.body.stmts.last(),
+                    Some(
+                        ast::Stmt::Return(_)
+                            | ast::Stmt::Throw(_)
+                            | ast::Stmt::Break(_)
+                            | ast::Stmt::Continue(_)
+                    )
+                );
+                let end: Option<syn::Expr> = if diverges {
+                    None
+                } else {
+                    Some(parse_quote!(None))
+                };
+                res.push(if exits.is_empty() {
+                    parse_quote! {
+                        match #block {
+                            Ok(()) => {}
+                            Err(#pat) => {
+                                #(#handler)*
+                            }
+                        }
+                    }
+                } else {
+                    parse_quote! {
+                        let flow = match #block {
+                            Ok(flow) => flow,
+                            Err(#pat) => {
+                                #(#handler)*
+                                #end
+                            }
+                        };
+                    }
+                });
+                res.extend(finalizer);
+            }
+            None if finalizer.is_empty() && exits.is_empty() => res.push(parse_quote!(#block?;)),
+            None if finalizer.is_empty() => res.push(parse_quote!(let flow = #block?;)),
+            None => {
+                res.push(parse_quote!(let result = #block;));
+                res.extend(finalizer);
+                res.push(if exits.is_empty() {
+                    parse_quote!(result?;)
+                } else {
+                    parse_quote!(let flow = result?;)
+                });
+            }
+        }
+        if !exits.is_empty() {
+            self.flows.set(true);
+            let arms = exits.iter().map(|e| -> syn::Arm {
+                match e {
+                    Exit::Return => {
+                        let s = self.leave_with(Some(parse_quote!(value)));
+                        parse_quote!(Some(Flow::Return(value)) => { #s })
+                    }
+                    Exit::Break(l) => {
+                        let s = self.break_stmt(l.as_deref());
+                        let l = flow_label(l.as_deref());
+                        parse_quote!(Some(Flow::Break(#l)) => { #s })
+                    }
+                    Exit::Continue(l) => {
+                        let s = self.continue_stmt(l.as_deref());
+                        let l = flow_label(l.as_deref());
+                        parse_quote!(Some(Flow::Continue(#l)) => { #s })
+                    }
+                }
+            });
+            res.push(parse_quote! {
+                match flow {
+                    #(#arms)*
+                    _ => {}
+                }
+            });
+        }
+        res
+    }
+
+    pub(super) fn is_error_class(&self, name: &str) -> bool {
+        self.error_classes.iter().any(|c| c.ident.as_ref() == name)
+    }
+
+    /// `Error` enum with a variant holding the message of each class extending `Error`
+    ///
+    /// `Message` stands for `Error` itself, which `Error::new` creates. The enum is generated when
+    /// there are such classes or a body uses errors, and `Flow` follows when a `try` block is left
+    /// early.
+    pub fn error_enum(&self) -> Vec<syn::Item> {
+        let mut items = Vec::new();
+        if self.flows.get() {
+            items.push(parse_quote! {
+                #[derive(Debug)]
+                pub enum Flow<T> {
+                    Return(T),
+                    Break(Option<&'static str>),
+                    Continue(Option<&'static str>)
+                }
+            });
+        }
+        if self.error_classes.is_empty() && !self.uses_error.get() {
+            return items;
+        }
+        let variants: Vec<_> = self.error_classes.iter().map(|c| ident(&c.ident)).collect();
+        let names = self
+            .error_classes
+            .iter()
+            .map(|c| format!("{}: {{}}", c.ident.as_ref()));
+        let mut error: Vec<syn::Item> = vec![
+            parse_quote! {
+                #[derive(Debug)]
+                pub enum Error {
+                    Message(String),
+                    #(#variants(String)),*
+                }
+            },
+            parse_quote! {
+                impl Error {
+                    pub fn new(message: impl Into<String>) -> Self { Error::Message(message.into()) }
+                }
+            },
+            parse_quote! {
+                impl std::fmt::Display for Error {
+                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        match self {
+                            Error::Message(message) => f.write_str(message),
+                            #(Error::#variants(message) => write!(f, #names, message)),*
+                        }
+                    }
+                }
+            },
+            parse_quote!(impl std::error::Error for Error {}),
+        ];
+        error.extend(items);
+        error
+    }
+
+    /// `new TimeoutError(message)` into the variant holding the message
+    pub(super) fn new_error(&self, class: &ast::Ident, args: Vec<syn::Expr>) -> syn::Expr {
+        let variant = ident(class);
+        match args.into_iter().next() {
+            Some(message) => {
+                let message = paren(message, POSTFIX);
+                parse_quote!(Error::#variant(#message.into()))
+            }
+            None => parse_quote!(Error::#variant(String::new()))
+        }
+    }
+
+    /// `e instanceof TimeoutError` matches the variants of the class and its subclasses
+    pub(super) fn instance_of(&self, e: syn::Expr, class: &str) -> syn::Expr {
+        let variants = self
+            .error_classes
+            .iter()
+            .filter(|c| self.descends(c, class))
+            .map(|c| ident(&c.ident));
+        parse_quote!(matches!(#e, #(Error::#variants(..))|*))
+    }
+
+    fn descends(&self, c: &ast::ClassDecl, ancestor: &str) -> bool {
+        if c.ident.as_ref() == ancestor {
+            return true;
+        }
+        match c.class.super_class.as_deref() {
+            Some(ast::Expr::Ident(parent)) => self
+                .classes
+                .get(parent.as_ref())
+                .map(|p| self.descends(p, ancestor))
+                .unwrap_or(false),
+            _ => false
+        }
+    }
+}
+
+/// Whether a class extends `Error` directly or through other classes
+pub(super) fn extends_error(classes: &HashMap<&str, &ast::ClassDecl>, c: &ast::Class) -> bool {
+    match c.super_class.as_deref() {
+        Some(ast::Expr::Ident(parent)) if parent.as_ref() == "Error" => true,
+        Some(ast::Expr::Ident(parent)) => classes
+            .get(parent.as_ref())
+            .map(|p| extends_error(classes, &p.class))
+            .unwrap_or(false),
+        _ => false
+    }
+}
+
+/// `-> T` into `-> Result<T, Error>`
+pub(super) fn result(output: &syn::ReturnType) -> syn::ReturnType {
+    match output {
+        syn::ReturnType::Default => parse_quote!(-> Result<(), Error>),
+        syn::ReturnType::Type(_, t) => parse_quote!(-> Result<#t, Error>)
+    }
+}
+
+/// Functions that throw or call a function that throws
+pub(super) fn throwing<'a>(functions: &[&'a ast::FnDecl]) -> HashSet<&'a str> {
+    let mut throwing = HashSet::new();
+    loop {
+        let found: Vec<_> = functions
+            .iter()
+            .filter(|f| !throwing.contains(f.ident.as_ref()))
+            .filter(|f| {
+                f.function
+                    .body
+                    .as_ref()
+                    .map(|b| throws(b, &throwing))
+                    .unwrap_or(false)
+            })
+            .map(|f| f.ident.as_ref())
+            .collect();
+        if found.is_empty() {
+            return throwing;
+        }
+        throwing.extend(found);
+    }
+}
+
+/// Whether a body throws, or calls one of the throwing functions, outside of the `try` blocks
+/// catching it
+pub(super) fn throws(b: &ast::BlockStmt, throwing: &HashSet<&str>) -> bool {
+    let mut v = Throws {
+        throwing,
+        found: false
+    };
+    b.visit_children_with(&mut v);
+    v.found
+}
+
+struct Throws<'t, 'a> {
+    throwing: &'t HashSet<&'a str>,
+    found: bool
+}
+
+impl Visit for Throws<'_, '_> {
+    fn visit_throw_stmt(&mut self, _n: &ast::ThrowStmt, _parent: &dyn Node) { self.found = true; }
+
+    fn visit_call_expr(&mut self, n: &ast::CallExpr, _parent: &dyn Node) {
+        if let ast::ExprOrSuper::Expr(callee) = &n.callee {
+            if let ast::Expr::Ident(i) = &**callee {
+                self.found |= self.throwing.contains(i.as_ref());
+            }
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_try_stmt(&mut self, n: &ast::TryStmt, _parent: &dyn Node) {
+        if n.handler.is_none() {
+            n.block.visit_children_with(self);
+        }
+        n.handler.visit_children_with(self);
+        n.finalizer.visit_children_with(self);
+    }
+
+    // Nested functions throw out of themselves
+    fn visit_function(&mut self, _n: &ast::Function, _parent: &dyn Node) {}
+
+    fn visit_arrow_expr(&mut self, _n: &ast::ArrowExpr, _parent: &dyn Node) {}
+
+    fn visit_class(&mut self, _n: &ast::Class, _parent: &dyn Node) {}
+}
+
+/// `Some("label")` identifying the target of a jump in a `Flow`
+pub(super) fn flow_label(label: Option<&str>) -> syn::Expr {
+    match label {
+        Some(l) => parse_quote!(Some(#l)),
+        None => parse_quote!(None)
+    }
+}
+
+fn awaits(b: &ast::BlockStmt) -> bool {
+    let mut v = Awaits(false);
+    b.visit_children_with(&mut v);
+    v.0
+}
+
+struct Awaits(bool);
+
+impl Visit for Awaits {
+    fn visit_await_expr(&mut self, _n: &ast::AwaitExpr, _parent: &dyn Node) { self.0 = true; }
+
+    fn visit_function(&mut self, _n: &ast::Function, _parent: &dyn Node) {}
+
+    fn visit_arrow_expr(&mut self, _n: &ast::ArrowExpr, _parent: &dyn Node) {}
+}
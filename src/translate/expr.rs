@@ -0,0 +1,525 @@
+use super::{case, prop_key, Context};
+use crate::{ident, ident_str};
+use proc_macro2::Span;
+use std::convert::TryFrom;
+use swc_ecma_ast as ast;
+use syn::parse_quote;
+
+/// Precedence of postfix expressions such as calls, fields and `.await`
+pub(super) const POSTFIX: u8 = 14;
+const UNARY: u8 = 13;
+
+impl Context<'_> {
+    /// Expressions without a Rust counterpart become `todo!()`
+    ///
+    /// Numeric literals are floats where the target holds floats, which reaches them through
+    /// arithmetic, conditionals and arrays.
+    pub(super) fn expr(&self, e: &ast::Expr) -> syn::Expr {
+        let typed = match e {
+            ast::Expr::Unary(u) => matches!(u.op, ast::UnaryOp::Minus | ast::UnaryOp::Plus),
+            ast::Expr::Lit(_)
+            | ast::Expr::Paren(_)
+            | ast::Expr::Bin(_)
+            | ast::Expr::Cond(_)
+            | ast::Expr::Array(_)
+            | ast::Expr::TsAs(_)
+            | ast::Expr::TsTypeAssertion(_)
+            | ast::Expr::TsConstAssertion(_) => true,
+            _ => false
+        };
+        let float = if typed {
+            self.float.get()
+        } else {
+            self.float.replace(false)
+        };
+        let res = self.untyped_expr(e);
+        self.float.set(float);
+        res
+    }
+
+    /// `e` for a target that holds floats or not
+    pub(super) fn typed_expr(&self, e: &ast::Expr, float: bool) -> syn::Expr {
+        let outer = self.float.replace(float);
+        let res = self.expr(e);
+        self.float.set(outer);
+        res
+    }
+
+    fn untyped_expr(&self, e: &ast::Expr) -> syn::Expr {
+        match e {
+            ast::Expr::Ident(i) if i.as_ref() == "undefined" => parse_quote!(None),
+            ast::Expr::Ident(i) => {
+                let i = ident(i);
+                parse_quote!(#i)
+            }
+            ast::Expr::This(_) => parse_quote!(self),
+            ast::Expr::Lit(l) => lit(l, self.float.get()),
+            ast::Expr::Tpl(t) => self.tpl(t),
+            ast::Expr::Array(a) => {
+                // A fractional element makes the numbers of the array floats
+                let float = self.float.get()
+                    || a.elems.iter().flatten().any(|e| match &*e.expr {
+                        ast::Expr::Lit(ast::Lit::Num(n)) => integer(n.value).is_none(),
+                        _ => false
+                    });
+                let elems = a.elems.iter().map(|e| match e {
+                    Some(ast::ExprOrSpread { spread: None, expr }) => self.typed_expr(expr, float),
+                    _ => parse_quote!(todo!())
+                });
+                parse_quote!(vec![#(#elems),*])
+            }
+            ast::Expr::Paren(p) => {
+                let e = self.expr(&p.expr);
+                parse_quote!((#e))
+            }
+            ast::Expr::Unary(u) => self.unary(u),
+            ast::Expr::Update(u) => {
+                let arg = self.place(&u.arg);
+                let one = number(1.0, self.is_float(&u.arg));
+                match u.op {
+                    ast::UpdateOp::PlusPlus => parse_quote!(#arg += #one),
+                    ast::UpdateOp::MinusMinus => parse_quote!(#arg -= #one)
+                }
+            }
+            ast::Expr::Bin(b) => self.binary(b),
+            ast::Expr::Assign(a) => self.assign(a),
+            ast::Expr::Cond(c) => {
+                let test = self.typed_expr(&c.test, false);
+                let cons = self.expr(&c.cons);
+                let alt = self.expr(&c.alt);
+                parse_quote!(if #test { #cons } else { #alt })
+            }
+            ast::Expr::Member(m) => self.member(m),
+            ast::Expr::Call(c) => self.call(c),
+            ast::Expr::New(n) => {
+                let args = self.args(n.args.as_deref().unwrap_or_default());
+                match &*n.callee {
+                    ast::Expr::Ident(i) if self.is_error_class(i.as_ref()) => {
+                        return self.new_error(i, args);
+                    }
+                    ast::Expr::Ident(i) if i.as_ref() == "Error" => self.uses_error.set(true),
+                    _ => {}
+                }
+                match self.class_path(&n.callee) {
+                    Some(path) if has_args(&path) => parse_quote!(<#path>::new(#(#args),*)),
+                    Some(path) => parse_quote!(#path::new(#(#args),*)),
+                    None => parse_quote!(todo!())
+                }
+            }
+            ast::Expr::Await(a) => {
+                let arg = paren(self.expr(&a.arg), POSTFIX);
+                parse_quote!(#arg.await)
+            }
+            ast::Expr::TsNonNull(n) => {
+                let e = paren(self.expr(&n.expr), POSTFIX);
+                parse_quote!(#e.unwrap())
+            }
+            ast::Expr::TsAs(ast::TsAsExpr { expr, .. })
+            | ast::Expr::TsTypeAssertion(ast::TsTypeAssertion { expr, .. })
+            | ast::Expr::TsConstAssertion(ast::TsConstAssertion { expr, .. }) => self.expr(expr),
+            _ => parse_quote!(todo!())
+        }
+    }
+
+    fn unary(&self, u: &ast::UnaryExpr) -> syn::Expr {
+        let arg = paren(self.expr(&u.arg), UNARY);
+        match u.op {
+            ast::UnaryOp::Bang | ast::UnaryOp::Tilde => parse_quote!(!#arg),
+            ast::UnaryOp::Minus => parse_quote!(-#arg),
+            ast::UnaryOp::Plus => self.expr(&u.arg),
+            ast::UnaryOp::TypeOf | ast::UnaryOp::Void | ast::UnaryOp::Delete => {
+                parse_quote!(todo!())
+            }
+        }
+    }
+
+    /// `**` and `??` become method calls since Rust has no such operators
+    ///
+    /// The operands of arithmetic and comparisons are floats when either of them is.
+    fn binary(&self, b: &ast::BinExpr) -> syn::Expr {
+        use ast::BinaryOp as Op;
+        let arithmetic = matches!(b.op, Op::Add | Op::Sub | Op::Mul | Op::Div | Op::Mod);
+        let comparison = matches!(
+            b.op,
+            Op::EqEq | Op::EqEqEq | Op::NotEq | Op::NotEqEq | Op::Lt | Op::LtEq | Op::Gt | Op::GtEq
+        );
+        let float = (arithmetic && self.float.get())
+            || (arithmetic || comparison) && (self.is_float(&b.left) || self.is_float(&b.right));
+        let op: syn::BinOp = match b.op {
+            ast::BinaryOp::EqEq | ast::BinaryOp::EqEqEq => parse_quote!(==),
+            ast::BinaryOp::NotEq | ast::BinaryOp::NotEqEq => parse_quote!(!=),
+            ast::BinaryOp::Lt => parse_quote!(<),
+            ast::BinaryOp::LtEq => parse_quote!(<=),
+            ast::BinaryOp::Gt => parse_quote!(>),
+            ast::BinaryOp::GtEq => parse_quote!(>=),
+            ast::BinaryOp::LShift => parse_quote!(<<),
+            ast::BinaryOp::RShift | ast::BinaryOp::ZeroFillRShift => parse_quote!(>>),
+            ast::BinaryOp::Add => parse_quote!(+),
+            ast::BinaryOp::Sub => parse_quote!(-),
+            ast::BinaryOp::Mul => parse_quote!(*),
+            ast::BinaryOp::Div => parse_quote!(/),
+            ast::BinaryOp::Mod => parse_quote!(%),
+            ast::BinaryOp::BitOr => parse_quote!(|),
+            ast::BinaryOp::BitXor => parse_quote!(^),
+            ast::BinaryOp::BitAnd => parse_quote!(&),
+            ast::BinaryOp::LogicalOr => parse_quote!(||),
+            ast::BinaryOp::LogicalAnd => parse_quote!(&&),
+            ast::BinaryOp::Exp => return self.power(&b.left, &b.right),
+            ast::BinaryOp::NullishCoalescing => {
+                let float = self.float.get() || self.is_float(&b.left);
+                let left = paren(self.typed_expr(&b.left, false), POSTFIX);
+                let right = self.typed_expr(&b.right, float);
+                return parse_quote!(#left.unwrap_or(#right));
+            }
+            ast::BinaryOp::In => {
+                let (left, right) = (self.expr(&b.left), paren(self.expr(&b.right), POSTFIX));
+                return parse_quote!(#right.contains_key(&#left));
+            }
+            ast::BinaryOp::InstanceOf => {
+                return match &*b.right {
+                    ast::Expr::Ident(i) if self.is_error_class(i.as_ref()) => {
+                        self.instance_of(self.expr(&b.left), i.as_ref())
+                    }
+                    _ => parse_quote!(todo!())
+                };
+            }
+        };
+        let p = bin_precedence(&op);
+        // Comparisons do not chain in Rust
+        let left_min = if p == 5 { p + 1 } else { p };
+        let left = paren(self.typed_expr(&b.left, float), left_min);
+        let right = paren(self.typed_expr(&b.right, float), p + 1);
+        parse_quote!(#left #op #right)
+    }
+
+    /// Whether an operand is a float, by its declared type or as a fractional literal
+    fn is_float(&self, e: &ast::Expr) -> bool {
+        match e {
+            ast::Expr::Lit(ast::Lit::Num(n)) => integer(n.value).is_none(),
+            ast::Expr::Paren(p) => self.is_float(&p.expr),
+            ast::Expr::Unary(ast::UnaryExpr {
+                op: ast::UnaryOp::Minus,
+                arg,
+                ..
+            }) => self.is_float(arg),
+            e => self
+                .expr_type(e)
+                .map(|t| holds_float(&self.ty(&t)))
+                .unwrap_or(false)
+        }
+    }
+
+    /// `**` raises integer literals with `i64::pow` when the power fits in `i64` and anything
+    /// else as `f64`
+    fn power(&self, base: &ast::Expr, exp: &ast::Expr) -> syn::Expr {
+        let int = |e: &ast::Expr| match e {
+            ast::Expr::Lit(ast::Lit::Num(n)) if !self.float.get() => integer(n.value),
+            _ => None
+        };
+        let pow = int(base).zip(int(exp).and_then(|e| u32::try_from(e).ok()));
+        if let Some((base, exp)) = pow.filter(|(b, e)| b.checked_pow(*e).is_some()) {
+            let (base, exp) = (
+                proc_macro2::Literal::i64_unsuffixed(base),
+                proc_macro2::Literal::u32_unsuffixed(exp)
+            );
+            return parse_quote!(i64::pow(#base, #exp));
+        }
+        let float = |e: &ast::Expr| -> syn::Expr {
+            match e {
+                ast::Expr::Lit(ast::Lit::Num(n)) if n.value.is_finite() => {
+                    let n = proc_macro2::Literal::f64_suffixed(n.value);
+                    parse_quote!(#n)
+                }
+                e => {
+                    let e = paren(self.expr(e), UNARY);
+                    parse_quote!(#e as f64)
+                }
+            }
+        };
+        let (base, exp) = (paren(float(base), POSTFIX), float(exp));
+        parse_quote!(#base.powf(#exp))
+    }
+
+    fn assign(&self, a: &ast::AssignExpr) -> syn::Expr {
+        let left = match &a.left {
+            ast::PatOrExpr::Expr(e) => self.place(e),
+            ast::PatOrExpr::Pat(p) => match &**p {
+                ast::Pat::Ident(b) => {
+                    let i = ident(&b.id);
+                    parse_quote!(#i)
+                }
+                ast::Pat::Expr(e) => self.place(e),
+                _ => return parse_quote!(todo!())
+            }
+        };
+        let float = match &a.left {
+            ast::PatOrExpr::Expr(e) => self.is_float(e),
+            ast::PatOrExpr::Pat(p) => match &**p {
+                ast::Pat::Ident(b) => self.is_float(&ast::Expr::Ident(b.id.clone())),
+                ast::Pat::Expr(e) => self.is_float(e),
+                _ => false
+            }
+        };
+        let left = paren(left, 2);
+        let right = paren(self.typed_expr(&a.right, float), 1);
+        match a.op {
+            ast::AssignOp::Assign => parse_quote!(#left = #right),
+            ast::AssignOp::AddAssign => parse_quote!(#left += #right),
+            ast::AssignOp::SubAssign => parse_quote!(#left -= #right),
+            ast::AssignOp::MulAssign => parse_quote!(#left *= #right),
+            ast::AssignOp::DivAssign => parse_quote!(#left /= #right),
+            ast::AssignOp::ModAssign => parse_quote!(#left %= #right),
+            ast::AssignOp::LShiftAssign => parse_quote!(#left <<= #right),
+            ast::AssignOp::RShiftAssign | ast::AssignOp::ZeroFillRShiftAssign => {
+                parse_quote!(#left >>= #right)
+            }
+            ast::AssignOp::BitOrAssign => parse_quote!(#left |= #right),
+            ast::AssignOp::BitXorAssign => parse_quote!(#left ^= #right),
+            ast::AssignOp::BitAndAssign => parse_quote!(#left &= #right),
+            ast::AssignOp::ExpAssign
+            | ast::AssignOp::AndAssign
+            | ast::AssignOp::OrAssign
+            | ast::AssignOp::NullishAssign => parse_quote!(todo!())
+        }
+    }
+
+    /// Expression that is assigned to or mutably borrowed
+    fn place(&self, e: &ast::Expr) -> syn::Expr {
+        let assigned = self.assigned.replace(true);
+        let e = self.expr(e);
+        self.assigned.set(assigned);
+        e
+    }
+
+    /// Fields are snake cased like the struct fields they refer to and `super` is the parent field
+    ///
+    /// The trait of an abstract class reaches its fields through `base()`, or `base_mut()` to
+    /// assign them.
+    fn member(&self, m: &ast::MemberExpr) -> syn::Expr {
+        let obj = match &m.obj {
+            ast::ExprOrSuper::Expr(o) => match (&**o, self.base.get()) {
+                (ast::Expr::This(_), Some(_)) if self.assigned.get() => {
+                    parse_quote!(self.base_mut())
+                }
+                (ast::Expr::This(_), Some(_)) => parse_quote!(self.base()),
+                (o, _) => paren(self.expr(o), POSTFIX)
+            },
+            ast::ExprOrSuper::Super(_) => self.super_field()
+        };
+        if m.computed {
+            let assigned = self.assigned.replace(false);
+            let prop = self.expr(&m.prop);
+            self.assigned.set(assigned);
+            return parse_quote!(#obj[#prop]);
+        }
+        match member_name(&m.prop) {
+            Some(name) => parse_quote!(#obj.#name),
+            None => parse_quote!(todo!())
+        }
+    }
+
+    /// Calls of members become method calls and `super(..)` constructs the parent
+    fn call(&self, c: &ast::CallExpr) -> syn::Expr {
+        let assigned = self.assigned.replace(false);
+        let args = self.args(&c.args);
+        self.assigned.set(assigned);
+        let callee = match &c.callee {
+            ast::ExprOrSuper::Super(_) => {
+                return match &*self.super_class.borrow() {
+                    Some((path, _)) if has_args(path) => parse_quote!(<#path>::new(#(#args),*)),
+                    Some((path, _)) => parse_quote!(#path::new(#(#args),*)),
+                    None => parse_quote!(todo!())
+                };
+            }
+            ast::ExprOrSuper::Expr(e) => &**e
+        };
+        match callee {
+            ast::Expr::Member(m) if !m.computed => {
+                let receiver = match &m.obj {
+                    // Methods of the fields of `&mut self` may mutate them
+                    ast::ExprOrSuper::Expr(o) if self.base.get() == Some(true) => {
+                        paren(self.place(o), POSTFIX)
+                    }
+                    ast::ExprOrSuper::Expr(o) => paren(self.expr(o), POSTFIX),
+                    ast::ExprOrSuper::Super(_) => self.super_field()
+                };
+                match member_name(&m.prop) {
+                    Some(method) => parse_quote!(#receiver.#method(#(#args),*)),
+                    None => parse_quote!(todo!())
+                }
+            }
+            ast::Expr::Ident(i) if self.throwing.contains(i.as_ref()) => {
+                let callee = ident(i);
+                parse_quote!(#callee(#(#args),*)?)
+            }
+            callee => {
+                let callee = paren(self.expr(callee), POSTFIX);
+                parse_quote!(#callee(#(#args),*))
+            }
+        }
+    }
+
+    fn args(&self, args: &[ast::ExprOrSpread]) -> Vec<syn::Expr> {
+        args.iter()
+            .map(|a| match a.spread {
+                None => self.expr(&a.expr),
+                Some(_) => parse_quote!(todo!())
+            })
+            .collect()
+    }
+
+    /// Template literals into `format!`
+    fn tpl(&self, t: &ast::Tpl) -> syn::Expr {
+        let mut fmt = String::new();
+        for (i, q) in t.quasis.iter().enumerate() {
+            let s = q.cooked.as_ref().unwrap_or(&q.raw);
+            fmt.push_str(&s.value.replace('{', "{{").replace('}', "}}"));
+            if i < t.exprs.len() {
+                fmt.push_str("{}");
+            }
+        }
+        if t.exprs.is_empty() {
+            let s = fmt.replace("{{", "{").replace("}}", "}");
+            return parse_quote!(#s);
+        }
+        let args = t.exprs.iter().map(|e| self.expr(e));
+        parse_quote!(format!(#fmt, #(#args),*))
+    }
+
+    /// `self.<parent field>` standing for `super`
+    fn super_field(&self) -> syn::Expr {
+        match &*self.super_class.borrow() {
+            Some((_, field)) => parse_quote!(self.#field),
+            None => parse_quote!(todo!())
+        }
+    }
+}
+
+fn lit(l: &ast::Lit, float: bool) -> syn::Expr {
+    match l {
+        ast::Lit::Str(s) => {
+            let s = &*s.value;
+            parse_quote!(#s)
+        }
+        ast::Lit::Bool(b) => {
+            let b = b.value;
+            parse_quote!(#b)
+        }
+        ast::Lit::Null(_) => parse_quote!(None),
+        ast::Lit::Num(n) => number(n.value, float),
+        ast::Lit::BigInt(b) => {
+            let n = syn::LitInt::new(&b.value.to_string(), Span::call_site());
+            parse_quote!(#n)
+        }
+        _ => parse_quote!(todo!())
+    }
+}
+
+/// Numeric literal that is an integer when the value fits in `i64`, unless a float is required
+pub(super) fn number(value: f64, float: bool) -> syn::Expr {
+    if value.is_nan() {
+        parse_quote!(f64::NAN)
+    } else if value == f64::INFINITY {
+        parse_quote!(f64::INFINITY)
+    } else if value == f64::NEG_INFINITY {
+        parse_quote!(f64::NEG_INFINITY)
+    } else if let Some(i) = integer(value).filter(|_| !float) {
+        let n = proc_macro2::Literal::i64_unsuffixed(i);
+        parse_quote!(#n)
+    } else {
+        let n = proc_macro2::Literal::f64_unsuffixed(value);
+        parse_quote!(#n)
+    }
+}
+
+/// The value as `i64` when it is integral and in range
+pub(super) fn integer(value: f64) -> Option<i64> {
+    if value.fract() == 0.0 && value >= i64::MIN as f64 && value < i64::MAX as f64 {
+        Some(value as i64)
+    } else {
+        None
+    }
+}
+
+/// Whether a type is a float or holds floats, like `Option<f64>` and `Vec<f64>`
+pub(super) fn holds_float(ty: &syn::Type) -> bool {
+    let last = match ty {
+        syn::Type::Path(p) if p.qself.is_none() => p.path.segments.last(),
+        _ => None
+    };
+    match last {
+        Some(s) if s.ident == "f64" || s.ident == "f32" => true,
+        Some(s) if s.ident == "Option" || s.ident == "Vec" || s.ident == "Box" => {
+            match &s.arguments {
+                syn::PathArguments::AngleBracketed(a) => match a.args.first() {
+                    Some(syn::GenericArgument::Type(t)) => holds_float(t),
+                    _ => false
+                },
+                _ => false
+            }
+        }
+        _ => false
+    }
+}
+
+fn member_name(prop: &ast::Expr) -> Option<syn::Ident> {
+    let key = match prop {
+        ast::Expr::PrivateName(p) => p.id.as_ref().to_owned(),
+        prop => prop_key(prop)?
+    };
+    Some(ident_str(&case::snake(&key)))
+}
+
+fn has_args(path: &syn::Path) -> bool {
+    path.segments
+        .iter()
+        .any(|s| !matches!(s.arguments, syn::PathArguments::None))
+}
+
+/// Parenthesizes `e` unless it binds at least as tightly as `min`
+pub(super) fn paren(e: syn::Expr, min: u8) -> syn::Expr {
+    if precedence(&e) < min {
+        parse_quote!((#e))
+    } else {
+        e
+    }
+}
+
+fn precedence(e: &syn::Expr) -> u8 {
+    match e {
+        syn::Expr::Closure(_)
+        | syn::Expr::Return(_)
+        | syn::Expr::Break(_)
+        | syn::Expr::Yield(_) => 0,
+        syn::Expr::Assign(_) | syn::Expr::AssignOp(_) => 1,
+        syn::Expr::Range(_) => 2,
+        syn::Expr::Binary(b) => bin_precedence(&b.op),
+        syn::Expr::Cast(_) => 12,
+        syn::Expr::Unary(_) | syn::Expr::Reference(_) => UNARY,
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(_) | syn::Lit::Float(_),
+            ..
+        }) => UNARY,
+        _ => POSTFIX
+    }
+}
+
+fn bin_precedence(op: &syn::BinOp) -> u8 {
+    match op {
+        syn::BinOp::Or(_) => 3,
+        syn::BinOp::And(_) => 4,
+        syn::BinOp::Eq(_)
+        | syn::BinOp::Ne(_)
+        | syn::BinOp::Lt(_)
+        | syn::BinOp::Le(_)
+        | syn::BinOp::Gt(_)
+        | syn::BinOp::Ge(_) => 5,
+        syn::BinOp::BitOr(_) => 6,
+        syn::BinOp::BitXor(_) => 7,
+        syn::BinOp::BitAnd(_) => 8,
+        syn::BinOp::Shl(_) | syn::BinOp::Shr(_) => 9,
+        syn::BinOp::Add(_) | syn::BinOp::Sub(_) => 10,
+        syn::BinOp::Mul(_) | syn::BinOp::Div(_) | syn::BinOp::Rem(_) => 11,
+        _ => 1
+    }
+}
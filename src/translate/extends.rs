@@ -0,0 +1,96 @@
+use super::{case, traits::is_trait, Context};
+use crate::{config::Parents, ident_str};
+use swc_ecma_ast as ast;
+use syn::parse_quote;
+
+impl Context<'_> {
+    /// Fields of the `extends` parents followed by the own fields, which override inherited ones
+    pub(super) fn interface_fields(&self, i: &ast::TsInterfaceDecl) -> Vec<syn::Field> {
+        let inherited = i
+            .extends
+            .iter()
+            .flat_map(|e| self.parent_fields(&e.expr, &e.type_args))
+            .collect();
+        merge(inherited, self.fields(&i.id.sym, &i.body.body, None))
+    }
+
+    /// Fields of `A & { b: B }` when every member is an object type
+    pub(super) fn intersection_fields(
+        &self,
+        owner: &str,
+        t: &ast::TsIntersectionType
+    ) -> Option<Vec<syn::Field>> {
+        let mut fields = Vec::new();
+        for t in &t.types {
+            let fs = match &**t {
+                ast::TsType::TsTypeLit(l) => self.fields(owner, &l.members, None),
+                ast::TsType::TsTypeRef(r) => self.parent_fields(&r.type_name, &r.type_params),
+                ast::TsType::TsParenthesizedType(p) => match &*p.type_ann {
+                    ast::TsType::TsUnionOrIntersectionType(
+                        ast::TsUnionOrIntersectionType::TsIntersectionType(i)
+                    ) => self.intersection_fields(owner, i)?,
+                    _ => return None
+                },
+                _ => return None
+            };
+            fields = merge(fields, fs);
+        }
+        Some(fields)
+    }
+
+    /// Inlined fields of a known interface or the parent embedded as a `#[serde(flatten)]` field
+    fn parent_fields(
+        &self,
+        name: &ast::TsEntityName,
+        args: &Option<ast::TsTypeParamInstantiation>
+    ) -> Vec<syn::Field> {
+        let known = match name {
+            ast::TsEntityName::Ident(i) if args.is_none() => self.interfaces.get(i.as_ref()),
+            _ => None
+        };
+        match known {
+            Some(i) if is_trait(i) => Vec::new(),
+            Some(i) if self.config.parents == Parents::Inline => self.interface_fields(i),
+            _ => {
+                let path = self.type_ref_path(name, args);
+                let last = path.segments.last().map(|s| s.ident.to_string());
+                let field = ident_str(&case::snake(last.as_deref().unwrap_or_default()));
+                vec![syn::Field {
+                    attrs: vec![parse_quote!(#[serde(flatten)])],
+                    vis: parse_quote!(pub),
+                    ident: Some(field),
+                    colon_token: Some(Default::default()),
+                    ty: syn::Type::Path(syn::TypePath { qself: None, path })
+                }]
+            }
+        }
+    }
+
+    /// Parents that were turned into traits become supertraits
+    pub(super) fn supertraits(&self, i: &ast::TsInterfaceDecl) -> Vec<syn::Path> {
+        i.extends
+            .iter()
+            .filter(|e| match &e.expr {
+                ast::TsEntityName::Ident(p) => self
+                    .interfaces
+                    .get(p.as_ref())
+                    .map(|p| is_trait(p))
+                    .unwrap_or(false),
+                ast::TsEntityName::TsQualifiedName(_) => false
+            })
+            .map(|e| self.type_ref_path(&e.expr, &e.type_args))
+            .collect()
+    }
+}
+
+/// Later fields replace earlier ones of the same name
+fn merge(inherited: Vec<syn::Field>, own: Vec<syn::Field>) -> Vec<syn::Field> {
+    let mut fields: Vec<syn::Field> = Vec::new();
+    for f in inherited.into_iter().chain(own) {
+        match fields.iter_mut().find(|g| g.ident == f.ident) {
+            Some(g) => *g = f,
+            None => fields.push(f)
+        }
+    }
+    fields
+}
@@ -0,0 +1,235 @@
+use super::{case, prop_key, ty::optional, Context};
+use std::{collections::HashMap, fmt};
+use swc_common::Span;
+use swc_ecma_ast as ast;
+use swc_ecma_visit::{Node, Visit, VisitWith};
+use syn::__private::ToTokens;
+
+/// Last words of property names that hold integers
+const INTEGER_WORDS: &[&str] = &[
+    "timeout", "width", "height", "count", "index", "length", "port", "delay", "retries", "limit",
+    "status", "code", "depth", "line", "column", "ms", "pid"
+];
+
+/// Phrases of JSDoc that describe integers
+const INTEGER_DOCS: &[&str] = &["in milliseconds", "integer", "number of"];
+
+/// The type chosen for a `number` field
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberChoice {
+    /// `Owner.field` of the TypeScript declaration
+    pub field: String,
+    pub ty: String,
+    pub reason: NumberReason
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumberReason {
+    Override,
+    FractionalLiteral,
+    Doc(String),
+    Name,
+    IntegerLiteral,
+    Default
+}
+
+impl fmt::Display for NumberChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match &self.reason {
+            NumberReason::Override => "overridden by config".to_owned(),
+            NumberReason::FractionalLiteral => "assigned a fractional literal".to_owned(),
+            NumberReason::Doc(phrase) => format!("documented as {:?}", phrase),
+            NumberReason::Name => "named like an integer".to_owned(),
+            NumberReason::IntegerLiteral => "assigned only integer literals".to_owned(),
+            NumberReason::Default => "default".to_owned()
+        };
+        write!(f, "{}: {} ({})", self.field, self.ty, reason)
+    }
+}
+
+/// Whether numeric literals assigned to a property name are integers
+#[derive(Debug, Default, Clone, Copy)]
+pub(super) struct LiteralUse {
+    integer: bool,
+    fractional: bool
+}
+
+impl Context<'_> {
+    /// `number` and `number | undefined` become the integer or float type chosen for the field
+    pub(super) fn field_type(
+        &self,
+        owner: &str,
+        key: &str,
+        span: Span,
+        type_ann: &Option<ast::TsTypeAnn>
+    ) -> syn::Type {
+        match type_ann.as_ref().and_then(|a| nullable_number(&a.type_ann)) {
+            Some(nullable) => {
+                let t = self.number(owner, key, span);
+                if nullable {
+                    optional(t)
+                } else {
+                    t
+                }
+            }
+            None => self.type_ann_or_any(type_ann)
+        }
+    }
+
+    fn number(&self, owner: &str, key: &str, span: Span) -> syn::Type {
+        let field = format!("{}.{}", owner, key);
+        let overridden = self
+            .numbers
+            .get(&*field)
+            .or_else(|| self.numbers.get(key))
+            .cloned();
+        let literals = self.literals.get(key).copied().unwrap_or_default();
+        let (ty, reason) = if let Some(t) = overridden {
+            (t, NumberReason::Override)
+        } else if literals.fractional {
+            (
+                self.types["number"].clone(),
+                NumberReason::FractionalLiteral
+            )
+        } else if let Some(phrase) = self.integer_doc(span) {
+            (self.integer.clone(), NumberReason::Doc(phrase.to_owned()))
+        } else if is_integer_name(key) {
+            (self.integer.clone(), NumberReason::Name)
+        } else if literals.integer {
+            (self.integer.clone(), NumberReason::IntegerLiteral)
+        } else {
+            (self.types["number"].clone(), NumberReason::Default)
+        };
+        let choice = NumberChoice {
+            field,
+            ty: ty.to_token_stream().to_string(),
+            reason
+        };
+        let mut choices = self.number_choices.borrow_mut();
+        // Utility types translate the fields of their source again
+        match choices.iter_mut().find(|c| c.field == choice.field) {
+            Some(c) => *c = choice,
+            None => choices.push(choice)
+        }
+        ty
+    }
+
+    fn integer_doc(&self, span: Span) -> Option<&'static str> {
+        self.comments.with_leading(span.lo, |comments| {
+            INTEGER_DOCS.iter().copied().find(|phrase| {
+                comments
+                    .iter()
+                    .any(|c| c.text.to_lowercase().contains(phrase))
+            })
+        })
+    }
+
+    pub fn number_choices(&self) -> Vec<NumberChoice> { self.number_choices.borrow().clone() }
+}
+
+/// `Some(nullable)` for `number`, `number | null` and `number | undefined`
+fn nullable_number(t: &ast::TsType) -> Option<bool> {
+    match t {
+        ast::TsType::TsKeywordType(ast::TsKeywordType {
+            kind: ast::TsKeywordTypeKind::TsNumberKeyword,
+            ..
+        }) => Some(false),
+        ast::TsType::TsParenthesizedType(p) => nullable_number(&p.type_ann),
+        ast::TsType::TsUnionOrIntersectionType(ast::TsUnionOrIntersectionType::TsUnionType(u)) => {
+            let mut number = false;
+            for t in &u.types {
+                match &**t {
+                    ast::TsType::TsKeywordType(ast::TsKeywordType {
+                        kind:
+                            ast::TsKeywordTypeKind::TsNullKeyword
+                            | ast::TsKeywordTypeKind::TsUndefinedKeyword,
+                        ..
+                    }) => {}
+                    t if !number && nullable_number(t) == Some(false) => number = true,
+                    _ => return None
+                }
+            }
+            if number {
+                Some(u.types.len() > 1)
+            } else {
+                None
+            }
+        }
+        _ => None
+    }
+}
+
+fn is_integer_name(key: &str) -> bool {
+    case::snake(key)
+        .rsplit('_')
+        .next()
+        .map(|w| INTEGER_WORDS.contains(&w))
+        .unwrap_or(false)
+}
+
+/// Numeric literals assigned to each property name in a module
+pub(super) fn literal_uses(m: &ast::Module) -> HashMap<String, LiteralUse> {
+    let mut v = Literals(HashMap::new());
+    m.visit_children_with(&mut v);
+    v.0
+}
+
+struct Literals(HashMap<String, LiteralUse>);
+
+impl Literals {
+    fn record(&mut self, key: Option<String>, value: &ast::Expr) {
+        let (key, n) = match (key, number_literal(value)) {
+            (Some(key), Some(n)) => (key, n),
+            _ => return
+        };
+        let u = self.0.entry(key).or_default();
+        if n.fract() == 0.0 {
+            u.integer = true;
+        } else {
+            u.fractional = true;
+        }
+    }
+}
+
+impl Visit for Literals {
+    fn visit_key_value_prop(&mut self, n: &ast::KeyValueProp, _parent: &dyn Node) {
+        let key = match &n.key {
+            ast::PropName::Ident(i) => Some(i.as_ref().to_owned()),
+            ast::PropName::Str(s) => Some(s.value.to_string()),
+            _ => None
+        };
+        self.record(key, &n.value);
+        n.visit_children_with(self);
+    }
+
+    fn visit_assign_expr(&mut self, n: &ast::AssignExpr, _parent: &dyn Node) {
+        if let ast::PatOrExpr::Expr(e) = &n.left {
+            if let ast::Expr::Member(m) = &**e {
+                if !m.computed {
+                    self.record(prop_key(&m.prop), &n.right);
+                }
+            }
+        }
+        n.visit_children_with(self);
+    }
+
+    fn visit_class_prop(&mut self, n: &ast::ClassProp, _parent: &dyn Node) {
+        if let Some(value) = &n.value {
+            self.record(prop_key(&n.key), value);
+        }
+        n.visit_children_with(self);
+    }
+}
+
+fn number_literal(e: &ast::Expr) -> Option<f64> {
+    match e {
+        ast::Expr::Lit(ast::Lit::Num(n)) => Some(n.value),
+        ast::Expr::Unary(ast::UnaryExpr {
+            op: ast::UnaryOp::Minus,
+            arg,
+            ..
+        }) => number_literal(arg).map(|n| -n),
+        ast::Expr::Paren(p) => number_literal(&p.expr),
+        _ => None
+    }
+}
@@ -0,0 +1,390 @@
+use super::{case, Context};
+use crate::{
+    config::{Callbacks, Overloads},
+    ident, ident_str
+};
+use std::collections::HashMap;
+use swc_ecma_ast as ast;
+use syn::parse_quote;
+
+/// One of the signatures declared for an overloaded function
+pub(super) struct Overload<'b> {
+    pub params: Vec<ast::Pat>,
+    pub type_params: &'b Option<ast::TsTypeParamDecl>,
+    pub return_type: &'b Option<ast::TsTypeAnn>,
+    pub is_async: bool,
+    /// Whether it is a constructor signature, which returns `Self`
+    pub constructs: bool
+}
+
+impl<'b> From<&'b ast::Function> for Overload<'b> {
+    fn from(f: &'b ast::Function) -> Self {
+        Self {
+            params: f.params.iter().map(|p| p.pat.clone()).collect(),
+            type_params: &f.type_params,
+            return_type: &f.return_type,
+            is_async: f.is_async,
+            constructs: false
+        }
+    }
+}
+
+/// Parameters and body implementing the overloads, with the value the body ends with if any
+pub(super) struct Implementation<'b> {
+    pub params: Vec<ast::Pat>,
+    pub body: Option<&'b ast::BlockStmt>,
+    pub value: Option<syn::Expr>
+}
+
+impl<'b> From<&'b ast::Function> for Implementation<'b> {
+    fn from(f: &'b ast::Function) -> Self {
+        Self {
+            params: f.params.iter().map(|p| p.pat.clone()).collect(),
+            body: f.body.as_ref(),
+            value: None
+        }
+    }
+}
+
+/// Signatures of a function declared more than once, with its implementation
+pub struct Overloaded<'a> {
+    pub signatures: Vec<&'a ast::Function>,
+    pub implementation: Option<&'a ast::Function>
+}
+
+impl Context<'_> {
+    /// Overloads of a module level function, preceded by the enum of their arguments if any
+    pub fn overloaded_fn(
+        &self,
+        name: &str,
+        vis: syn::Visibility,
+        fs: &Overloaded<'_>
+    ) -> Vec<syn::Item> {
+        let overloads: Vec<_> = fs.signatures.iter().map(|&f| Overload::from(f)).collect();
+        let implementation = fs.implementation.map(Implementation::from);
+        let (fns, args) = self.overloads("", name, &vis, &overloads, implementation.as_ref());
+        args.into_iter()
+            .chain(fns.into_iter().map(|(sig, body)| {
+                let body = body.unwrap_or_else(|| parse_quote!({}));
+                parse_quote!(#vis #sig #body)
+            }))
+            .collect()
+    }
+
+    /// Suffixed signatures, or one signature taking the returned enum of argument shapes, with
+    /// the body of the implementation if any
+    ///
+    /// When every overload takes a distinct string literal at the same position, such as the
+    /// event name of `on`, the literal names the overload and is dropped from its parameters.
+    /// The enum is named after the owner, if any, and the function.
+    pub(super) fn overloads(
+        &self,
+        owner: &str,
+        name: &str,
+        vis: &syn::Visibility,
+        overloads: &[Overload<'_>],
+        implementation: Option<&Implementation<'_>>
+    ) -> (Vec<(syn::Signature, Option<syn::Block>)>, Option<syn::Item>) {
+        let literals = literal_position(overloads);
+        let shapes: Vec<(Option<&str>, Vec<ast::Pat>)> = overloads
+            .iter()
+            .enumerate()
+            .map(|(i, o)| match &literals {
+                Some((k, values)) => {
+                    let mut params = o.params.clone();
+                    params.remove(*k);
+                    (Some(&*values[i]), params)
+                }
+                None => (None, o.params.clone())
+            })
+            .collect();
+        match self.config.overloads {
+            Overloads::Suffixed => {
+                let sigs = overloads
+                    .iter()
+                    .zip(&shapes)
+                    .enumerate()
+                    .map(|(i, (o, (literal, params)))| {
+                        let name = match (literal, i) {
+                            (Some(l), _) => format!("{}_{}", case::snake(name), case::snake(l)),
+                            (None, 0) => case::snake(name),
+                            (None, i) => format!("{}{}", case::snake(name), i + 1)
+                        };
+                        let mut sig = self.signature(ident_str(&name), params, o);
+                        let literal = literals.as_ref().map(|(k, values)| (*k, &*values[i]));
+                        let body = implementation
+                            .map(|f| self.overload_body(f, &o.params, literal, &mut sig.output));
+                        (sig, body)
+                    })
+                    .collect();
+                (sigs, None)
+            }
+            Overloads::Enum => {
+                let (mut sig, pats, args) =
+                    self.overload_enum(owner, name, vis, overloads, &shapes);
+                let body = implementation.map(|f| {
+                    let output = sig.output.clone();
+                    let arms: Vec<_> = overloads
+                        .iter()
+                        .zip(pats)
+                        .enumerate()
+                        .map(|(i, (o, pat))| {
+                            let literal = literals.as_ref().map(|(k, values)| (*k, &*values[i]));
+                            sig.output = output.clone();
+                            let body = self.overload_body(f, &o.params, literal, &mut sig.output);
+                            quote_arm(pat, body)
+                        })
+                        .collect();
+                    parse_quote!({
+                        match args {
+                            #(#arms)*
+                        }
+                    })
+                });
+                (vec![(sig, body)], Some(args))
+            }
+        }
+    }
+
+    /// Body of the implementation for one overload, which first binds the parameters of the
+    /// implementation to the arguments of the overload
+    fn overload_body(
+        &self,
+        f: &Implementation<'_>,
+        params: &[ast::Pat],
+        literal: Option<(usize, &str)>,
+        output: &mut syn::ReturnType
+    ) -> syn::Block {
+        let bindings = f
+            .params
+            .iter()
+            .enumerate()
+            .filter_map(|(k, p)| -> Option<syn::Stmt> {
+                let name = pat_ident(p)?;
+                let value: syn::Expr = match (literal, params.get(k).and_then(pat_ident)) {
+                    (Some((i, l)), _) if i == k => parse_quote!(#l),
+                    (_, Some(arg)) if arg.as_ref() == name.as_ref() => return None,
+                    (_, Some(arg)) => {
+                        let arg = ident(arg);
+                        parse_quote!(#arg)
+                    }
+                    (_, None) => parse_quote!(None)
+                };
+                let name = ident(name);
+                Some(parse_quote!(let #name = #value;))
+            });
+        let bindings: Vec<_> = bindings.collect();
+        let before = output.clone();
+        let mut stmts = self.body(&f.params, f.body, output).stmts;
+        if let Some(value) = &f.value {
+            stmts.push(syn::Stmt::Expr(if *output == before {
+                value.clone()
+            } else {
+                parse_quote!(Ok(#value))
+            }));
+        }
+        parse_quote!({
+            #(#bindings)*
+            #(#stmts)*
+        })
+    }
+
+    fn overload_enum(
+        &self,
+        owner: &str,
+        name: &str,
+        vis: &syn::Visibility,
+        overloads: &[Overload<'_>],
+        shapes: &[(Option<&str>, Vec<ast::Pat>)]
+    ) -> (syn::Signature, Vec<syn::Pat>, syn::Item) {
+        let args = ident_str(&format!(
+            "{}{}Args",
+            case::pascal(owner),
+            case::pascal(name)
+        ));
+        let mut generics = syn::Generics::default();
+        for o in overloads {
+            for p in self.generics(o.type_params, false).params {
+                if !generics.params.iter().any(|q| q == &p) {
+                    generics.params.push(p);
+                }
+            }
+        }
+        if !generics.params.is_empty() {
+            generics.lt_token = Some(Default::default());
+            generics.gt_token = Some(Default::default());
+        }
+        let mut names: Vec<String> = shapes
+            .iter()
+            .map(|(literal, params)| match literal {
+                Some(l) => case::pascal(l),
+                None => case::pascal(
+                    &params
+                        .iter()
+                        .filter_map(|p| match p {
+                            ast::Pat::Ident(b) => Some(b.id.as_ref()),
+                            _ => None
+                        })
+                        .collect::<Vec<_>>()
+                        .join("_")
+                )
+            })
+            .collect();
+        let distinct = names
+            .iter()
+            .enumerate()
+            .all(|(i, n)| !n.is_empty() && !names[..i].contains(n));
+        if !distinct {
+            names = (1..=shapes.len())
+                .map(|i| format!("Overload{}", i))
+                .collect();
+        }
+        let mut pats = Vec::new();
+        let variants: Vec<syn::Variant> = names
+            .iter()
+            .zip(shapes)
+            .map(|(n, (_, params))| -> syn::Variant {
+                let variant = ident_str(n);
+                let fields = params
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, p)| match self.fn_arg(i, p, Callbacks::Boxed)? {
+                        syn::FnArg::Typed(t) => Some(t),
+                        syn::FnArg::Receiver(_) => None
+                    })
+                    .map(|t| {
+                        let (pat, ty) = (t.pat, t.ty);
+                        quote_field(&pat, &ty)
+                    })
+                    .collect::<Vec<_>>();
+                if fields.is_empty() {
+                    pats.push(parse_quote!(#args::#variant));
+                    parse_quote!(#variant)
+                } else {
+                    let names = fields.iter().filter_map(|f| f.ident.as_ref());
+                    pats.push(parse_quote!(#args::#variant { #(#names),* }));
+                    parse_quote!(#variant { #(#fields),* })
+                }
+            })
+            .collect();
+        let item = parse_quote! {
+            #vis enum #args #generics {
+                #(#variants),*
+            }
+        };
+        let outputs: Vec<_> = overloads.iter().map(|o| self.overload_output(o)).collect();
+        let asyncness = outputs.iter().find_map(|(a, _)| *a);
+        let output = match &outputs[..] {
+            [(_, first), rest @ ..] if rest.iter().all(|(_, o)| o == first) => first.clone(),
+            _ => {
+                let any = self.any();
+                parse_quote!(-> #any)
+            }
+        };
+        let (_, ty_generics, _) = generics.split_for_impl();
+        let name = ident_str(&case::snake(name));
+        (
+            parse_quote!(#asyncness fn #name #generics(args: #args #ty_generics) #output),
+            pats,
+            item
+        )
+    }
+
+    fn signature(&self, name: syn::Ident, params: &[ast::Pat], o: &Overload<'_>) -> syn::Signature {
+        let (generics, inputs) = self.fn_params(o.type_params, params);
+        let (asyncness, output) = self.overload_output(o);
+        parse_quote!(#asyncness fn #name #generics(#inputs) #output)
+    }
+
+    fn overload_output(&self, o: &Overload<'_>) -> (Option<syn::token::Async>, syn::ReturnType) {
+        if o.constructs {
+            (None, parse_quote!(-> Self))
+        } else {
+            self.async_return_type(o.return_type, o.is_async)
+        }
+    }
+}
+
+/// Module level functions declared with overload signatures
+pub fn overloaded_fns(m: &ast::Module) -> HashMap<&str, Overloaded<'_>> {
+    let mut fns: HashMap<&str, Vec<&ast::Function>> = HashMap::new();
+    for (_, decl) in super::decls(m) {
+        if let ast::Decl::Fn(f) = decl {
+            fns.entry(f.ident.as_ref()).or_default().push(&f.function);
+        }
+    }
+    fns.into_iter()
+        .filter_map(|(name, fs)| Some((name, signatures(fs)?)))
+        .collect()
+}
+
+/// The signatures and the implementation, when there are two signatures or one followed by an
+/// implementation
+pub(super) fn signatures(fs: Vec<&ast::Function>) -> Option<Overloaded<'_>> {
+    let (signatures, implementations): (Vec<_>, Vec<_>) =
+        fs.into_iter().partition(|f| f.body.is_none());
+    if signatures.len() >= 2 || !signatures.is_empty() && !implementations.is_empty() {
+        Some(Overloaded {
+            signatures,
+            implementation: implementations.into_iter().next()
+        })
+    } else {
+        None
+    }
+}
+
+/// Name bound by a parameter
+fn pat_ident(p: &ast::Pat) -> Option<&ast::Ident> {
+    match p {
+        ast::Pat::Ident(b) => Some(&b.id),
+        ast::Pat::Assign(a) => pat_ident(&a.left),
+        _ => None
+    }
+}
+
+/// Position and values of the parameter each overload types with a distinct string literal
+fn literal_position(overloads: &[Overload<'_>]) -> Option<(usize, Vec<String>)> {
+    let len = overloads.iter().map(|o| o.params.len()).min()?;
+    (0..len).find_map(|k| {
+        let values: Vec<String> = overloads
+            .iter()
+            .map(|o| match &o.params[k] {
+                ast::Pat::Ident(ast::BindingIdent {
+                    type_ann: Some(a), ..
+                }) => match &*a.type_ann {
+                    ast::TsType::TsLitType(ast::TsLitType {
+                        lit: ast::TsLit::Str(s),
+                        ..
+                    }) => Some(s.value.to_string()),
+                    _ => None
+                },
+                _ => None
+            })
+            .collect::<Option<_>>()?;
+        let distinct = values
+            .iter()
+            .enumerate()
+            .all(|(i, v)| !values[..i].contains(v));
+        if distinct {
+            Some((k, values))
+        } else {
+            None
+        }
+    })
+}
+
+fn quote_arm(pat: syn::Pat, body: syn::Block) -> syn::Arm { parse_quote!(#pat => #body) }
+
+fn quote_field(pat: &syn::Pat, ty: &syn::Type) -> syn::Field {
+    let ident = match pat {
+        syn::Pat::Ident(i) => Some(i.ident.clone()),
+        _ => None
+    };
+    syn::Field {
+        attrs: Vec::new(),
+        vis: syn::Visibility::Inherited,
+        ident,
+        colon_token: Some(Default::default()),
+        ty: ty.clone()
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::{HashMap, HashSet};
+use syn::parse_quote;
+
+/// Types that keep their elements on the heap, breaking a cycle of sizes
+const INDIRECT: &[&str] = &[
+    "Box", "Vec", "HashMap", "HashSet", "BTreeMap", "BTreeSet", "VecDeque", "Rc", "Arc"
+];
+
+/// Boxes fields of structs, enums and aliases that would contain themselves
+///
+/// One field is boxed at a time, in the order of the items, until no declared type reaches itself
+/// by value, so a cycle through several types gets a single `Box`.
+pub fn box_recursive(items: &mut [syn::Item]) {
+    let names: HashSet<syn::Ident> = items.iter().filter_map(name).cloned().collect();
+    loop {
+        let edges: HashMap<syn::Ident, Vec<syn::Ident>> = items
+            .iter_mut()
+            .filter_map(|item| {
+                let name = name(item)?.clone();
+                let mut held = Vec::new();
+                for ty in types(item) {
+                    held_by_value(ty, &names, &mut held);
+                }
+                Some((name, held))
+            })
+            .collect();
+        let cycle = items.iter_mut().find_map(|item| {
+            let owner = name(item)?.clone();
+            types(item).into_iter().find_map(|ty| {
+                let mut held = Vec::new();
+                held_by_value(ty, &names, &mut held);
+                let target = held.into_iter().find(|t| reaches(&edges, t, &owner))?;
+                Some((ty, target))
+            })
+        });
+        match cycle {
+            Some((ty, target)) => {
+                box_held(ty, &target);
+            }
+            None => break
+        }
+    }
+}
+
+fn name(item: &syn::Item) -> Option<&syn::Ident> {
+    match item {
+        syn::Item::Struct(s) => Some(&s.ident),
+        syn::Item::Enum(e) => Some(&e.ident),
+        syn::Item::Type(t) => Some(&t.ident),
+        _ => None
+    }
+}
+
+/// Types the item is made of
+fn types(item: &mut syn::Item) -> Vec<&mut syn::Type> {
+    match item {
+        syn::Item::Struct(s) => s.fields.iter_mut().map(|f| &mut f.ty).collect(),
+        syn::Item::Enum(e) => e
+            .variants
+            .iter_mut()
+            .flat_map(|v| v.fields.iter_mut().map(|f| &mut f.ty))
+            .collect(),
+        syn::Item::Type(t) => vec![&mut *t.ty],
+        _ => Vec::new()
+    }
+}
+
+/// Declared types a value of `ty` contains without indirection
+fn held_by_value(ty: &syn::Type, names: &HashSet<syn::Ident>, held: &mut Vec<syn::Ident>) {
+    match ty {
+        syn::Type::Path(p) if p.qself.is_none() => {
+            let last = match p.path.segments.last() {
+                Some(last) => last,
+                None => return
+            };
+            if p.path.segments.len() == 1 && names.contains(&last.ident) {
+                held.push(last.ident.clone());
+            }
+            if INDIRECT.iter().any(|i| last.ident == i) {
+                return;
+            }
+            if let syn::PathArguments::AngleBracketed(args) = &last.arguments {
+                for arg in &args.args {
+                    if let syn::GenericArgument::Type(t) = arg {
+                        held_by_value(t, names, held);
+                    }
+                }
+            }
+        }
+        syn::Type::Tuple(t) => t.elems.iter().for_each(|t| held_by_value(t, names, held)),
+        syn::Type::Array(a) => held_by_value(&a.elem, names, held),
+        syn::Type::Paren(p) => held_by_value(&p.elem, names, held),
+        syn::Type::Group(g) => held_by_value(&g.elem, names, held),
+        _ => {}
+    }
+}
+
+fn reaches(
+    edges: &HashMap<syn::Ident, Vec<syn::Ident>>,
+    from: &syn::Ident,
+    to: &syn::Ident
+) -> bool {
+    let mut seen = HashSet::new();
+    let mut stack = vec![from];
+    while let Some(n) = stack.pop() {
+        if n == to {
+            return true;
+        }
+        if seen.insert(n) {
+            stack.extend(edges.get(n).into_iter().flatten());
+        }
+    }
+    false
+}
+
+/// Wraps the first occurrence of `target` held by value in a `Box`
+fn box_held(ty: &mut syn::Type, target: &syn::Ident) -> bool {
+    match ty {
+        syn::Type::Path(p) if p.qself.is_none() => {
+            let single = p.path.segments.len() == 1;
+            let last = match p.path.segments.last_mut() {
+                Some(last) => last,
+                None => return false
+            };
+            if single && last.ident == *target {
+                *ty = parse_quote!(Box<#ty>);
+                return true;
+            }
+            if INDIRECT.iter().any(|i| last.ident == i) {
+                return false;
+            }
+            match &mut last.arguments {
+                syn::PathArguments::AngleBracketed(args) => {
+                    args.args.iter_mut().any(|arg| match arg {
+                        syn::GenericArgument::Type(t) => box_held(t, target),
+                        _ => false
+                    })
+                }
+                _ => false
+            }
+        }
+        syn::Type::Tuple(t) => t.elems.iter_mut().any(|t| box_held(t, target)),
+        syn::Type::Array(a) => box_held(&mut a.elem, target),
+        syn::Type::Paren(p) => box_held(&mut p.elem, target),
+        syn::Type::Group(g) => box_held(&mut g.elem, target),
+        _ => false
+    }
+}
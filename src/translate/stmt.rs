@@ -0,0 +1,546 @@
+use super::{
+    error::{self, Exit},
+    expr::holds_float,
+    prop_key, Context
+};
+use crate::ident;
+use proc_macro2::Span;
+use std::cell::Cell;
+use swc_ecma_ast as ast;
+use swc_ecma_visit::{Node, Visit, VisitWith};
+use syn::parse_quote;
+
+/// Statement that `break` and `continue` can jump out of
+pub(super) struct Jump {
+    kind: JumpKind,
+    /// Label in the source
+    label: Option<String>,
+    /// Label in Rust, only attached to a loop when a jump needs it
+    lifetime: syn::Lifetime,
+    /// Block around the body of a loop that `continue` breaks out of
+    body: Option<syn::Lifetime>,
+    used: Cell<bool>
+}
+
+#[derive(PartialEq)]
+enum JumpKind {
+    Loop,
+    Switch,
+    Block
+}
+
+impl Context<'_> {
+    /// Function declaration with its signature and body
+    pub fn function(&self, f: &ast::FnDecl, vis: syn::Visibility) -> syn::ItemFn {
+        let name = ident(&f.ident);
+        let (generics, inputs) = self.fn_params(
+            &f.function.type_params,
+            f.function.params.iter().map(|p| &p.pat)
+        );
+        let (asyncness, mut output) =
+            self.async_return_type(&f.function.return_type, f.function.is_async);
+        let body = self.body(
+            f.function.params.iter().map(|p| &p.pat),
+            f.function.body.as_ref(),
+            &mut output
+        );
+        parse_quote!(#vis #asyncness fn #name #generics(#inputs) #output #body)
+    }
+
+    /// Body of a function, empty for a signature
+    ///
+    /// A body that throws makes the function return a `Result`.
+    pub(super) fn body<'b>(
+        &self,
+        params: impl IntoIterator<Item = &'b ast::Pat>,
+        b: Option<&ast::BlockStmt>,
+        output: &mut syn::ReturnType
+    ) -> syn::Block {
+        let outer = self.bindings.borrow().clone();
+        let jumps = self.jumps.take();
+        let tries = self.tries.take();
+        for p in params {
+            self.bind(p);
+        }
+        let throws = b.map(|b| error::throws(b, &self.throwing)).unwrap_or(false);
+        let returns_result = self.returns_result.replace(throws);
+        let returns_float = self.returns_float.replace(match output {
+            syn::ReturnType::Type(_, t) => holds_float(t),
+            syn::ReturnType::Default => false
+        });
+        let mut stmts = b.map(|b| self.stmts(&b.stmts)).unwrap_or_default();
+        self.returns_result.set(returns_result);
+        self.returns_float.set(returns_float);
+        *self.jumps.borrow_mut() = jumps;
+        *self.tries.borrow_mut() = tries;
+        *self.bindings.borrow_mut() = outer;
+        if throws {
+            self.uses_error.set(true);
+            let returned = matches!(
+                b.and_then(|b| b.stmts.last()),
+                Some(ast::Stmt::Return(_) | ast::Stmt::Throw(_))
+            );
+            if *output == syn::ReturnType::Default && !returned {
+                stmts.push(syn::Stmt::Expr(parse_quote!(Ok(()))));
+            }
+            *output = error::result(output);
+        }
+        parse_quote!({ #(#stmts)* })
+    }
+
+    /// Remembers the annotated type of a binding for the translation of the expressions using it
+    fn bind(&self, p: &ast::Pat) {
+        match p {
+            ast::Pat::Ident(ast::BindingIdent {
+                id,
+                type_ann: Some(a)
+            }) => {
+                self.bindings
+                    .borrow_mut()
+                    .insert(id.as_ref().to_owned(), (*a.type_ann).clone());
+            }
+            ast::Pat::Assign(a) => self.bind(&a.left),
+            _ => {}
+        }
+    }
+
+    /// Declared type of a binding or of a property of an interface
+    pub(super) fn expr_type(&self, e: &ast::Expr) -> Option<ast::TsType> {
+        match e {
+            ast::Expr::Ident(i) => self.bindings.borrow().get(i.as_ref()).cloned(),
+            ast::Expr::Paren(p) => self.expr_type(&p.expr),
+            ast::Expr::Member(ast::MemberExpr {
+                obj: ast::ExprOrSuper::Expr(obj),
+                prop,
+                computed: false,
+                ..
+            }) => {
+                let key = prop_key(prop)?;
+                let t = self.expr_type(obj)?;
+                let members = self.object_members(&t)?;
+                members.iter().find_map(|m| match m {
+                    ast::TsTypeElement::TsPropertySignature(p)
+                        if prop_key(&p.key).as_deref() == Some(&*key) =>
+                    {
+                        Some((*p.type_ann.as_ref()?.type_ann).clone())
+                    }
+                    _ => None
+                })
+            }
+            _ => None
+        }
+    }
+
+    pub(super) fn stmts(&self, stmts: &[ast::Stmt]) -> Vec<syn::Stmt> {
+        stmts.iter().flat_map(|s| self.stmt(s)).collect()
+    }
+
+    fn stmt(&self, s: &ast::Stmt) -> Vec<syn::Stmt> {
+        match s {
+            ast::Stmt::Block(b) => {
+                let stmts = self.stmts(&b.stmts);
+                vec![parse_quote!({ #(#stmts)* })]
+            }
+            ast::Stmt::Empty(_) | ast::Stmt::Debugger(_) => Vec::new(),
+            ast::Stmt::Expr(e) => {
+                let e = self.expr(&e.expr);
+                vec![parse_quote!(#e;)]
+            }
+            ast::Stmt::Decl(ast::Decl::Var(v)) => self.var_decl(v),
+            ast::Stmt::Decl(ast::Decl::Fn(f)) => {
+                vec![syn::Stmt::Item(syn::Item::Fn(
+                    self.function(f, syn::Visibility::Inherited)
+                ))]
+            }
+            ast::Stmt::Return(r) => vec![self.return_stmt(r.arg.as_deref())],
+            ast::Stmt::Throw(t) => vec![self.throw(t)],
+            ast::Stmt::Try(t) => self.try_stmt(t),
+            ast::Stmt::Break(b) => vec![self.break_stmt(b.label.as_ref().map(|l| l.as_ref()))],
+            ast::Stmt::Continue(c) => {
+                vec![self.continue_stmt(c.label.as_ref().map(|l| l.as_ref()))]
+            }
+            ast::Stmt::If(i) => vec![syn::Stmt::Expr(self.if_expr(i))],
+            ast::Stmt::Switch(s) => vec![syn::Stmt::Expr(self.switch(s))],
+            ast::Stmt::Labeled(l) => match &*l.body {
+                ast::Stmt::While(_)
+                | ast::Stmt::DoWhile(_)
+                | ast::Stmt::For(_)
+                | ast::Stmt::ForOf(_) => self.loop_stmt(&l.body, Some(&l.label)),
+                body => {
+                    let label = lifetime(l.label.as_ref());
+                    self.jumps.borrow_mut().push(Jump {
+                        kind: JumpKind::Block,
+                        label: Some(l.label.as_ref().to_owned()),
+                        lifetime: label.clone(),
+                        body: None,
+                        used: Cell::new(true)
+                    });
+                    let block = self.block(body);
+                    self.jumps.borrow_mut().pop();
+                    vec![parse_quote!(#label: #block)]
+                }
+            },
+            ast::Stmt::While(_)
+            | ast::Stmt::DoWhile(_)
+            | ast::Stmt::For(_)
+            | ast::Stmt::ForOf(_) => self.loop_stmt(s, None),
+            _ => vec![parse_quote!(todo!();)]
+        }
+    }
+
+    /// Statement as the block of an `if` or a loop
+    fn block(&self, s: &ast::Stmt) -> syn::Block {
+        let stmts = match s {
+            ast::Stmt::Block(b) => self.stmts(&b.stmts),
+            s => self.stmt(s)
+        };
+        parse_quote!({ #(#stmts)* })
+    }
+
+    fn if_expr(&self, i: &ast::IfStmt) -> syn::Expr {
+        let test = self.expr(&i.test);
+        let then = self.block(&i.cons);
+        match i.alt.as_deref() {
+            None => parse_quote!(if #test #then),
+            Some(ast::Stmt::If(alt)) => {
+                let alt = self.if_expr(alt);
+                parse_quote!(if #test #then else #alt)
+            }
+            Some(alt) => {
+                let alt = self.block(alt);
+                parse_quote!(if #test #then else #alt)
+            }
+        }
+    }
+
+    /// `do-while` checks its condition at the end of a `loop` and `for` runs its update at the end
+    /// of a `while`
+    fn loop_stmt(&self, s: &ast::Stmt, label: Option<&ast::Ident>) -> Vec<syn::Stmt> {
+        match s {
+            ast::Stmt::While(w) => {
+                let test = self.expr(&w.test);
+                let (label, body) = self.loop_body(label, &w.body, false);
+                vec![parse_quote!(#label while #test { #(#body)* })]
+            }
+            ast::Stmt::DoWhile(d) => {
+                let test = not(self.expr(&d.test));
+                let (label, body) = self.loop_body(label, &d.body, true);
+                vec![parse_quote! {
+                    #label loop {
+                        #(#body)*
+                        if #test {
+                            break;
+                        }
+                    }
+                }]
+            }
+            ast::Stmt::For(f) => {
+                let init = match &f.init {
+                    Some(ast::VarDeclOrExpr::VarDecl(v)) => self.var_decl(v),
+                    Some(ast::VarDeclOrExpr::Expr(e)) => {
+                        let e = self.expr(e);
+                        vec![parse_quote!(#e;)]
+                    }
+                    None => Vec::new()
+                };
+                let (label, mut body) = self.loop_body(label, &f.body, f.update.is_some());
+                if let Some(update) = &f.update {
+                    let update = self.expr(update);
+                    body.push(parse_quote!(#update;));
+                }
+                let looped: syn::Stmt = match &f.test {
+                    Some(test) => {
+                        let test = self.expr(test);
+                        parse_quote!(#label while #test { #(#body)* })
+                    }
+                    None => parse_quote!(#label loop { #(#body)* })
+                };
+                if init.is_empty() {
+                    vec![looped]
+                } else {
+                    vec![parse_quote!({
+                        #(#init)*
+                        #looped
+                    })]
+                }
+            }
+            ast::Stmt::ForOf(f) => {
+                let pat = match &f.left {
+                    ast::VarDeclOrPat::VarDecl(v) => match v.decls.first() {
+                        Some(d) => self.pat(&d.name, v.kind != ast::VarDeclKind::Const).0,
+                        None => parse_quote!(_)
+                    },
+                    ast::VarDeclOrPat::Pat(p) => self.pat(p, false).0
+                };
+                let iter = self.expr(&f.right);
+                let (label, body) = self.loop_body(label, &f.body, false);
+                vec![parse_quote!(#label for #pat in #iter { #(#body)* })]
+            }
+            s => self.stmt(s)
+        }
+    }
+
+    /// Body of a loop with the label of the loop if it needs one
+    ///
+    /// When the loop runs something after its body, `continue` breaks out of a labeled block
+    /// around the body instead of skipping it.
+    fn loop_body(
+        &self,
+        label: Option<&ast::Ident>,
+        s: &ast::Stmt,
+        after: bool
+    ) -> (Option<syn::Label>, Vec<syn::Stmt>) {
+        let body = if after && continues(s, label) {
+            Some(self.fresh_lifetime("body"))
+        } else {
+            None
+        };
+        let jump = Jump {
+            kind: JumpKind::Loop,
+            label: label.map(|l| l.as_ref().to_owned()),
+            lifetime: label
+                .map(|l| lifetime(l.as_ref()))
+                .unwrap_or_else(|| self.fresh_lifetime("looped")),
+            body: body.clone(),
+            used: Cell::new(label.is_some())
+        };
+        self.jumps.borrow_mut().push(jump);
+        let stmts = self.block(s).stmts;
+        let jump = self.jumps.borrow_mut().pop().expect("pushed above");
+        let stmts = match body {
+            Some(body) => vec![parse_quote!(#body: { #(#stmts)* })],
+            None => stmts
+        };
+        let label = jump.used.get().then(|| syn::Label {
+            name: jump.lifetime,
+            colon_token: Default::default()
+        });
+        (label, stmts)
+    }
+
+    /// Statements of a `switch` case, in a labeled block when `break` leaves it from within
+    pub(super) fn case_block<'b>(
+        &self,
+        stmts: impl IntoIterator<Item = &'b ast::Stmt>
+    ) -> syn::Block {
+        let jump = Jump {
+            kind: JumpKind::Switch,
+            label: None,
+            lifetime: self.fresh_lifetime("case"),
+            body: None,
+            used: Cell::new(false)
+        };
+        self.jumps.borrow_mut().push(jump);
+        let stmts: Vec<syn::Stmt> = stmts.into_iter().flat_map(|s| self.stmt(s)).collect();
+        let jump = self.jumps.borrow_mut().pop().expect("pushed above");
+        if jump.used.get() {
+            let label = jump.lifetime;
+            parse_quote!({ #label: { #(#stmts)* } })
+        } else {
+            parse_quote!({ #(#stmts)* })
+        }
+    }
+
+    /// Lifetime for a generated label, numbered when an enclosing one has the same name
+    fn fresh_lifetime(&self, name: &str) -> syn::Lifetime {
+        let jumps = self.jumps.borrow();
+        let taken = |l: &syn::Lifetime| l.ident == name;
+        let name = if jumps
+            .iter()
+            .any(|j| taken(&j.lifetime) || j.body.as_ref().map(taken).unwrap_or(false))
+        {
+            format!("'{}{}", name, jumps.len())
+        } else {
+            format!("'{}", name)
+        };
+        syn::Lifetime::new(&name, Span::call_site())
+    }
+
+    /// `break` with the label of its loop when it is inside a labeled block of the loop
+    pub(super) fn break_stmt(&self, label: Option<&str>) -> syn::Stmt {
+        let jumps = self.jumps.borrow();
+        let target = match label {
+            Some(l) => jumps.iter().rposition(|j| j.label.as_deref() == Some(l)),
+            None => jumps.iter().rposition(|j| j.kind != JumpKind::Block)
+        };
+        if let Some(i) = target {
+            if self.leave_try(Some(i), Exit::Break(label.map(str::to_owned))) {
+                let label = error::flow_label(label);
+                return parse_quote!(return Ok(Some(Flow::Break(#label))););
+            }
+        }
+        match target.map(|i| (i, &jumps[i])) {
+            Some((i, j))
+                if label.is_some()
+                    || j.kind == JumpKind::Switch
+                    || j.body.is_some()
+                    || i + 1 < jumps.len() =>
+            {
+                j.used.set(true);
+                let l = &j.lifetime;
+                parse_quote!(break #l;)
+            }
+            _ => {
+                let label = label.map(lifetime);
+                parse_quote!(break #label;)
+            }
+        }
+    }
+
+    /// `continue` leaves the body block of a loop running something after its body
+    pub(super) fn continue_stmt(&self, label: Option<&str>) -> syn::Stmt {
+        let jumps = self.jumps.borrow();
+        let target = jumps.iter().rposition(|j| match label {
+            Some(l) => j.label.as_deref() == Some(l),
+            None => j.kind == JumpKind::Loop
+        });
+        if let Some(i) = target {
+            if self.leave_try(Some(i), Exit::Continue(label.map(str::to_owned))) {
+                let label = error::flow_label(label);
+                return parse_quote!(return Ok(Some(Flow::Continue(#label))););
+            }
+        }
+        match target.map(|i| (i, &jumps[i])) {
+            Some((
+                _,
+                Jump {
+                    body: Some(body), ..
+                }
+            )) => parse_quote!(break #body;),
+            Some((i, j)) if label.is_some() || i + 1 < jumps.len() => {
+                j.used.set(true);
+                let l = &j.lifetime;
+                parse_quote!(continue #l;)
+            }
+            _ => {
+                let label = label.map(lifetime);
+                parse_quote!(continue #label;)
+            }
+        }
+    }
+
+    /// `let` and `var` bind mutably and `const` immutably
+    fn var_decl(&self, v: &ast::VarDecl) -> Vec<syn::Stmt> {
+        let mutable = v.kind != ast::VarDeclKind::Const;
+        v.decls
+            .iter()
+            .map(|d| {
+                self.bind(&d.name);
+                let (pat, ty) = self.pat(&d.name, mutable);
+                let float = ty.as_ref().map(holds_float).unwrap_or(false);
+                let pat = match ty {
+                    Some(ty) => syn::Pat::Type(syn::PatType {
+                        attrs: Vec::new(),
+                        pat: Box::new(pat),
+                        colon_token: Default::default(),
+                        ty: Box::new(ty)
+                    }),
+                    None => pat
+                };
+                match &d.init {
+                    Some(init) => {
+                        let init = self.typed_expr(init, float);
+                        parse_quote!(let #pat = #init;)
+                    }
+                    None => parse_quote!(let #pat;)
+                }
+            })
+            .collect()
+    }
+
+    /// Binding pattern with its annotated type, where array destructuring becomes a tuple
+    pub(super) fn pat(&self, p: &ast::Pat, mutable: bool) -> (syn::Pat, Option<syn::Type>) {
+        let mutability = if mutable {
+            Some(<syn::Token![mut]>::default())
+        } else {
+            None
+        };
+        match p {
+            ast::Pat::Ident(b) => {
+                let name = ident(&b.id);
+                (
+                    parse_quote!(#mutability #name),
+                    b.type_ann.as_ref().map(|a| self.ty(&a.type_ann))
+                )
+            }
+            ast::Pat::Array(a) => {
+                let elems = a.elems.iter().map(|e| match e {
+                    Some(p) => self.pat(p, mutable).0,
+                    None => parse_quote!(_)
+                });
+                (
+                    parse_quote!((#(#elems),*)),
+                    a.type_ann.as_ref().map(|a| self.ty(&a.type_ann))
+                )
+            }
+            _ => (parse_quote!(_), None)
+        }
+    }
+}
+
+fn lifetime(label: &str) -> syn::Lifetime {
+    syn::Lifetime::new(&format!("'{}", label), Span::call_site())
+}
+
+/// Negation keeping atoms unparenthesized
+fn not(e: syn::Expr) -> syn::Expr {
+    match e {
+        syn::Expr::Path(_)
+        | syn::Expr::Lit(_)
+        | syn::Expr::Call(_)
+        | syn::Expr::MethodCall(_)
+        | syn::Expr::Field(_)
+        | syn::Expr::Paren(_) => parse_quote!(!#e),
+        e => parse_quote!(!(#e))
+    }
+}
+
+/// Whether the body of a loop continues it, by its label or from outside of nested loops
+fn continues(body: &ast::Stmt, label: Option<&ast::Ident>) -> bool {
+    let mut v = Continues {
+        label: label.map(|l| l.as_ref()),
+        depth: 0,
+        found: false
+    };
+    body.visit_children_with(&mut v);
+    v.found
+}
+
+struct Continues<'l> {
+    label: Option<&'l str>,
+    depth: usize,
+    found: bool
+}
+
+impl Continues<'_> {
+    fn nested(&mut self, n: &dyn VisitWith<Self>) {
+        self.depth += 1;
+        n.visit_children_with(self);
+        self.depth -= 1;
+    }
+}
+
+impl Visit for Continues<'_> {
+    fn visit_continue_stmt(&mut self, n: &ast::ContinueStmt, _parent: &dyn Node) {
+        self.found |= match &n.label {
+            Some(l) => self.label == Some(l.as_ref()),
+            None => self.depth == 0
+        };
+    }
+
+    fn visit_while_stmt(&mut self, n: &ast::WhileStmt, _parent: &dyn Node) { self.nested(n); }
+
+    fn visit_do_while_stmt(&mut self, n: &ast::DoWhileStmt, _parent: &dyn Node) { self.nested(n); }
+
+    fn visit_for_stmt(&mut self, n: &ast::ForStmt, _parent: &dyn Node) { self.nested(n); }
+
+    fn visit_for_of_stmt(&mut self, n: &ast::ForOfStmt, _parent: &dyn Node) { self.nested(n); }
+
+    fn visit_for_in_stmt(&mut self, n: &ast::ForInStmt, _parent: &dyn Node) { self.nested(n); }
+
+    fn visit_function(&mut self, _n: &ast::Function, _parent: &dyn Node) {}
+
+    fn visit_arrow_expr(&mut self, _n: &ast::ArrowExpr, _parent: &dyn Node) {}
+
+    fn visit_class(&mut self, _n: &ast::Class, _parent: &dyn Node) {}
+}
@@ -0,0 +1,151 @@
+use super::{
+    class::expr_path,
+    expr::{integer, number},
+    Context
+};
+use crate::ident_str;
+use swc_ecma_ast as ast;
+use syn::parse_quote;
+
+/// Enum generated for the type of a discriminant, with its values and variants
+type StringEnum<'b> = (syn::Ident, &'b [(String, syn::Ident)]);
+
+impl Context<'_> {
+    /// `switch` into `match`, where cases falling through share the arm of the next body
+    ///
+    /// A case with a body falling through runs the bodies after it in its own arm, and `default`
+    /// becomes the last arm wherever it is.
+    pub(super) fn switch(&self, s: &ast::SwitchStmt) -> syn::Expr {
+        let string_enum = self.discriminant_enum(&s.discriminant);
+        let float = self
+            .expr_type(&s.discriminant)
+            .map(|t| self.ty(&t) == parse_quote!(f64))
+            .unwrap_or(false);
+        let mut arms: Vec<syn::Arm> = Vec::new();
+        let mut default_arm: Option<syn::Arm> = None;
+        let mut tests: Vec<&ast::Expr> = Vec::new();
+        let mut default = false;
+        let mut strings = false;
+        for (i, case) in s.cases.iter().enumerate() {
+            match &case.test {
+                Some(t) => tests.push(t),
+                None => default = true
+            }
+            if case.cons.is_empty() && i + 1 < s.cases.len() {
+                continue;
+            }
+            let body = self.case_body(&s.cases[i..]);
+            if default {
+                default_arm = Some(parse_quote!(_ => #body));
+            } else {
+                let pats: Option<Vec<syn::Pat>> = tests
+                    .iter()
+                    .map(|t| case_pat(t, string_enum.as_ref(), float, &mut strings))
+                    .collect();
+                arms.push(match pats {
+                    Some(pats) => parse_quote!(#(#pats)|* => #body),
+                    None => {
+                        let discriminant = self.expr(&s.discriminant);
+                        let tests = tests.iter().map(|t| match t {
+                            ast::Expr::Lit(ast::Lit::Num(n)) if float => number(n.value, true),
+                            t => self.expr(t)
+                        });
+                        parse_quote!(_ if #(#discriminant == #tests)||* => #body)
+                    }
+                });
+            }
+            tests.clear();
+            default = false;
+        }
+        arms.push(default_arm.unwrap_or_else(|| parse_quote!(_ => {})));
+        let discriminant = self.expr(&s.discriminant);
+        let discriminant: syn::Expr = if strings && string_enum.is_none() {
+            parse_quote!(#discriminant.as_str())
+        } else {
+            discriminant
+        };
+        parse_quote! {
+            match #discriminant {
+                #(#arms)*
+            }
+        }
+    }
+
+    /// Body of the first case followed by the bodies it falls through to, without the final
+    /// `break`
+    fn case_body(&self, cases: &[ast::SwitchCase]) -> syn::Block {
+        let mut body: Vec<&ast::Stmt> = Vec::new();
+        for case in cases {
+            body.extend(&case.cons);
+            if matches!(
+                case.cons.last(),
+                Some(
+                    ast::Stmt::Break(_)
+                        | ast::Stmt::Continue(_)
+                        | ast::Stmt::Return(_)
+                        | ast::Stmt::Throw(_)
+                )
+            ) {
+                break;
+            }
+        }
+        if let Some(ast::Stmt::Break(ast::BreakStmt { label: None, .. })) = body.last() {
+            body.pop();
+        }
+        self.case_block(body)
+    }
+
+    /// Generated enum of a discriminant typed with a string literal union or a string enum
+    fn discriminant_enum(&self, e: &ast::Expr) -> Option<StringEnum<'_>> {
+        let t = self.expr_type(e)?;
+        match &t {
+            ast::TsType::TsTypeRef(ast::TsTypeRef {
+                type_name: ast::TsEntityName::Ident(i),
+                type_params: None,
+                ..
+            }) => {
+                let values = self.string_enums.get(i.as_ref())?;
+                Some((ident_str(i.as_ref()), values))
+            }
+            _ => None
+        }
+    }
+}
+
+/// Pattern of a case, `None` when it can only be compared with `==`
+///
+/// Numbers are only patterns for an integer discriminant.
+fn case_pat(
+    test: &ast::Expr,
+    string_enum: Option<&StringEnum<'_>>,
+    float: bool,
+    strings: &mut bool
+) -> Option<syn::Pat> {
+    match test {
+        ast::Expr::Lit(ast::Lit::Str(s)) => match string_enum {
+            Some((name, values)) => {
+                let (_, variant) = values.iter().find(|(v, _)| *v == *s.value)?;
+                Some(parse_quote!(#name::#variant))
+            }
+            None => {
+                *strings = true;
+                let s = &*s.value;
+                Some(parse_quote!(#s))
+            }
+        },
+        ast::Expr::Lit(ast::Lit::Num(n)) if !float => {
+            let n = proc_macro2::Literal::i64_unsuffixed(integer(n.value)?);
+            Some(parse_quote!(#n))
+        }
+        ast::Expr::Lit(ast::Lit::Bool(b)) => {
+            let b = b.value;
+            Some(parse_quote!(#b))
+        }
+        ast::Expr::Member(m) if !m.computed => {
+            let path = expr_path(test)?;
+            Some(parse_quote!(#path))
+        }
+        ast::Expr::Paren(p) => case_pat(&p.expr, string_enum, float, strings),
+        _ => None
+    }
+}
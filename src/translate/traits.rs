@@ -0,0 +1,253 @@
+use super::{
+    case,
+    class::{mutates_this, prop_name},
+    overload::Overload,
+    prop_key,
+    ty::{fn_param_pat, optional},
+    Context
+};
+use crate::{ident, ident_str};
+use std::collections::{HashMap, HashSet};
+use swc_ecma_ast as ast;
+use syn::parse_quote;
+
+/// Methods of a trait made from an interface, as its implementations declare them
+pub(super) struct TraitMethods {
+    pub names: Vec<syn::Ident>,
+    /// Methods taking `&mut self`
+    pub mutable: HashSet<syn::Ident>,
+    /// Getters of the properties, which fields of the same name provide
+    pub getters: Vec<(syn::Ident, syn::Type)>
+}
+
+impl Context<'_> {
+    /// Method signatures become required methods and properties become getters
+    ///
+    /// Methods take `&mut self` when a class implementing the interface mutates `this` in them.
+    pub(super) fn interface_trait(
+        &self,
+        i: &ast::TsInterfaceDecl,
+        vis: syn::Visibility
+    ) -> syn::ItemTrait {
+        let name = ident(&i.id);
+        let generics = self.generics(&i.type_params, true);
+        let supertraits = self.supertraits(i);
+        let colon = if supertraits.is_empty() {
+            None
+        } else {
+            Some(<syn::Token![:]>::default())
+        };
+        let overloaded = overloaded_signatures(i);
+        let mutated = self.mutated_methods(i);
+        let mut emitted = HashSet::new();
+        let mut items = Vec::new();
+        for e in &i.body.body {
+            let key = match e {
+                ast::TsTypeElement::TsMethodSignature(m) if !m.computed => prop_key(&m.key),
+                _ => None
+            };
+            match key.and_then(|key| Some((overloaded.get(&key)?, key))) {
+                Some((ms, key)) => {
+                    if emitted.insert(key.clone()) {
+                        let receiver = receiver(mutated.contains(&key));
+                        items.extend(
+                            self.overloaded_trait_items(&i.id.sym, &key, &vis, ms, receiver)
+                        );
+                    }
+                }
+                None => items.extend(self.trait_item(e, &mutated))
+            }
+        }
+        parse_quote! {
+            #vis trait #name #generics #colon #(#supertraits)+* {
+                #(#items)*
+            }
+        }
+    }
+
+    fn trait_item(
+        &self,
+        e: &ast::TsTypeElement,
+        mutated: &HashSet<String>
+    ) -> Option<syn::TraitItem> {
+        match e {
+            ast::TsTypeElement::TsMethodSignature(m) if !m.computed => {
+                let key = prop_key(&m.key)?;
+                let name = ident_str(&case::snake(&key));
+                let pats: Vec<_> = m.params.iter().map(fn_param_pat).collect();
+                let (generics, mut inputs) = self.fn_params(&m.type_params, &pats);
+                inputs.insert(0, receiver(mutated.contains(&key)));
+                let (asyncness, output) = self.async_return_type(&m.type_ann, false);
+                Some(parse_quote!(#asyncness fn #name #generics(#inputs) #output;))
+            }
+            ast::TsTypeElement::TsPropertySignature(p) if !p.computed => {
+                let (name, ty) = self.getter(p)?;
+                Some(parse_quote!(fn #name(&self) -> #ty;))
+            }
+            _ => None
+        }
+    }
+
+    fn getter(&self, p: &ast::TsPropertySignature) -> Option<(syn::Ident, syn::Type)> {
+        let name = ident_str(&case::snake(&prop_key(&p.key)?));
+        let ty = self.type_ann_or_any(&p.type_ann);
+        let ty = if p.optional { optional(ty) } else { ty };
+        Some((name, ty))
+    }
+
+    /// Required methods of an overloaded method signature, whose enum of arguments is generated
+    /// next to the trait
+    fn overloaded_trait_items(
+        &self,
+        owner: &str,
+        key: &str,
+        vis: &syn::Visibility,
+        ms: &[&ast::TsMethodSignature],
+        receiver: syn::FnArg
+    ) -> Vec<syn::TraitItem> {
+        let (sigs, args) = self.overloads(owner, key, vis, &method_overloads(ms), None);
+        self.generated.borrow_mut().extend(args);
+        sigs.into_iter()
+            .map(|(mut sig, _)| {
+                sig.inputs.insert(0, receiver.clone());
+                parse_quote!(#sig;)
+            })
+            .collect()
+    }
+
+    /// Methods `interface_trait` declares, with the names of the overloads of overloaded
+    /// methods
+    pub(super) fn trait_methods(&self, i: &ast::TsInterfaceDecl) -> TraitMethods {
+        let overloaded = overloaded_signatures(i);
+        let mutated = self.mutated_methods(i);
+        let mut names = Vec::new();
+        let mut mutable = HashSet::new();
+        let mut getters = Vec::new();
+        for e in &i.body.body {
+            let key = match e {
+                ast::TsTypeElement::TsMethodSignature(ast::TsMethodSignature {
+                    key,
+                    computed: false,
+                    ..
+                }) => prop_key(key),
+                ast::TsTypeElement::TsPropertySignature(p) if !p.computed => {
+                    getters.extend(self.getter(p));
+                    prop_key(&p.key)
+                }
+                _ => None
+            };
+            let start = names.len();
+            match key.as_ref().map(|k| (overloaded.get(k), k)) {
+                Some((Some(ms), key)) => {
+                    let overloads = method_overloads(ms);
+                    let (sigs, _) = self.overloads(
+                        &i.id.sym,
+                        key,
+                        &syn::Visibility::Inherited,
+                        &overloads,
+                        None
+                    );
+                    for (sig, _) in sigs {
+                        if !names.contains(&sig.ident) {
+                            names.push(sig.ident);
+                        }
+                    }
+                }
+                Some((None, key)) => names.push(ident_str(&case::snake(key))),
+                None => {}
+            }
+            if key.map(|k| mutated.contains(&k)).unwrap_or(false) {
+                mutable.extend(names[start..].iter().cloned());
+            }
+        }
+        TraitMethods {
+            names,
+            mutable,
+            getters
+        }
+    }
+
+    /// Keys of the methods that a class implementing the interface mutates `this` in
+    fn mutated_methods(&self, i: &ast::TsInterfaceDecl) -> HashSet<String> {
+        self.classes
+            .values()
+            .filter(|c| {
+                c.class.implements.iter().any(|e| match &e.expr {
+                    ast::TsEntityName::Ident(n) => n.sym == i.id.sym,
+                    ast::TsEntityName::TsQualifiedName(_) => false
+                })
+            })
+            .flat_map(|c| &c.class.body)
+            .filter_map(|m| match m {
+                ast::ClassMember::Method(m)
+                    if !m.is_static
+                        && m.kind == ast::MethodKind::Method
+                        && mutates_this(&m.function) =>
+                {
+                    prop_name(&m.key)
+                }
+                _ => None
+            })
+            .collect()
+    }
+
+    /// Interfaces among `implements` that were turned into traits, with their type arguments
+    pub(super) fn implemented_traits<'b>(
+        &'b self,
+        c: &'b ast::Class
+    ) -> impl Iterator<Item = (syn::Path, &'b ast::TsInterfaceDecl)> + 'b {
+        c.implements.iter().filter_map(move |e| match &e.expr {
+            ast::TsEntityName::Ident(i) => self
+                .interfaces
+                .get(i.as_ref())
+                .copied()
+                .filter(|i| is_trait(i))
+                .map(|i| (self.type_ref_path(&e.expr, &e.type_args), i)),
+            ast::TsEntityName::TsQualifiedName(_) => None
+        })
+    }
+}
+
+fn receiver(mutable: bool) -> syn::FnArg {
+    if mutable {
+        parse_quote!(&mut self)
+    } else {
+        parse_quote!(&self)
+    }
+}
+
+/// Interfaces declaring methods are contracts rather than data
+pub(super) fn is_trait(i: &ast::TsInterfaceDecl) -> bool {
+    i.body
+        .body
+        .iter()
+        .any(|e| matches!(e, ast::TsTypeElement::TsMethodSignature(_)))
+}
+
+/// Method signatures declared more than once by name
+pub(super) fn overloaded_signatures(
+    i: &ast::TsInterfaceDecl
+) -> HashMap<String, Vec<&ast::TsMethodSignature>> {
+    let mut overloaded: HashMap<String, Vec<&ast::TsMethodSignature>> = HashMap::new();
+    for e in &i.body.body {
+        if let ast::TsTypeElement::TsMethodSignature(m) = e {
+            if let Some(key) = prop_key(&m.key).filter(|_| !m.computed) {
+                overloaded.entry(key).or_default().push(m);
+            }
+        }
+    }
+    overloaded.retain(|_, ms| ms.len() >= 2);
+    overloaded
+}
+
+fn method_overloads<'b>(ms: &[&'b ast::TsMethodSignature]) -> Vec<Overload<'b>> {
+    ms.iter()
+        .map(|m| Overload {
+            params: m.params.iter().map(fn_param_pat).collect(),
+            type_params: &m.type_params,
+            return_type: &m.type_ann,
+            is_async: false,
+            constructs: false
+        })
+        .collect()
+}
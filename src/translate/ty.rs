@@ -0,0 +1,481 @@
+use super::{prop_key, traits::is_trait, Context};
+use crate::{config::Callbacks, ident, ident_str};
+use swc_ecma_ast as ast;
+use syn::{fold::Fold, parse_quote, punctuated::Punctuated};
+
+impl Context<'_> {
+    /// Type parameters and parameters, where callbacks become type parameters if configured
+    pub fn fn_params<'p>(
+        &self,
+        type_params: &Option<ast::TsTypeParamDecl>,
+        pats: impl IntoIterator<Item = &'p ast::Pat>
+    ) -> (syn::Generics, Punctuated<syn::FnArg, syn::token::Comma>) {
+        let mut hoist = HoistImpl(self.generics(type_params, false));
+        let inputs = pats
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, p)| self.fn_arg(i, p, self.config.callbacks))
+            .map(|arg| hoist.fold_fn_arg(arg))
+            .collect();
+        (hoist.0, inputs)
+    }
+
+    pub(super) fn fn_arg(
+        &self,
+        i: usize,
+        pat: &ast::Pat,
+        callbacks: Callbacks
+    ) -> Option<syn::FnArg> {
+        let (name, ty) = match pat {
+            ast::Pat::Ident(ast::BindingIdent { id, .. }) if id.as_ref() == "this" => return None,
+            ast::Pat::Ident(ast::BindingIdent { id, type_ann }) if id.optional => {
+                (ident(id), optional(self.param_type(type_ann, callbacks)))
+            }
+            ast::Pat::Ident(ast::BindingIdent { id, type_ann }) => {
+                (ident(id), self.param_type(type_ann, callbacks))
+            }
+            ast::Pat::Assign(ast::AssignPat {
+                left,
+                type_ann: None,
+                ..
+            }) => return self.fn_arg(i, left, callbacks),
+            ast::Pat::Assign(ast::AssignPat { left, type_ann, .. }) => {
+                let name = match &**left {
+                    ast::Pat::Ident(b) => ident(&b.id),
+                    _ => ident_str(&format!("arg{}", i))
+                };
+                (name, self.param_type(type_ann, callbacks))
+            }
+            ast::Pat::Rest(ast::RestPat { arg, type_ann, .. }) => {
+                let name = match &**arg {
+                    ast::Pat::Ident(b) => ident(&b.id),
+                    _ => ident_str(&format!("arg{}", i))
+                };
+                (name, self.type_ann_or_any(type_ann))
+            }
+            ast::Pat::Array(ast::ArrayPat { type_ann, .. })
+            | ast::Pat::Object(ast::ObjectPat { type_ann, .. }) => (
+                ident_str(&format!("arg{}", i)),
+                self.type_ann_or_any(type_ann)
+            ),
+            ast::Pat::Invalid(_) | ast::Pat::Expr(_) => return None
+        };
+        Some(parse_quote!(#name: #ty))
+    }
+
+    /// `async` and the return type with `Promise<T>` resolved to `T`
+    pub fn async_return_type(
+        &self,
+        type_ann: &Option<ast::TsTypeAnn>,
+        is_async: bool
+    ) -> (Option<syn::token::Async>, syn::ReturnType) {
+        let t = type_ann.as_ref().map(|a| &*a.type_ann);
+        match t.and_then(promise) {
+            Some(resolved) => (Some(Default::default()), self.output(resolved)),
+            None if is_async => (Some(Default::default()), self.output(t)),
+            None => (None, self.output(t))
+        }
+    }
+
+    fn output(&self, t: Option<&ast::TsType>) -> syn::ReturnType {
+        match t {
+            None
+            | Some(ast::TsType::TsKeywordType(ast::TsKeywordType {
+                kind: ast::TsKeywordTypeKind::TsVoidKeyword,
+                ..
+            })) => syn::ReturnType::Default,
+            Some(t) => {
+                let t = self.ty(t);
+                parse_quote!(-> #t)
+            }
+        }
+    }
+
+    /// `impl Fn(A)` for a callback to be turned into a type parameter
+    fn param_type(&self, type_ann: &Option<ast::TsTypeAnn>, callbacks: Callbacks) -> syn::Type {
+        let f = match type_ann.as_ref().map(|a| fn_type(&a.type_ann)) {
+            Some(Some(f)) if callbacks == Callbacks::Generic => f,
+            _ => return self.type_ann_or_any(type_ann)
+        };
+        let bound = self.fn_bound(f);
+        parse_quote!(impl #bound)
+    }
+
+    /// `(a: A) => R` into `Fn(A) -> R`
+    fn fn_bound(&self, f: &ast::TsFnType) -> syn::TraitBound {
+        let pats: Vec<_> = f.params.iter().map(fn_param_pat).collect();
+        let inputs = pats.iter().enumerate().filter_map(|(i, p)| {
+            match self.fn_arg(i, p, Callbacks::Boxed)? {
+                syn::FnArg::Typed(t) => Some(*t.ty),
+                syn::FnArg::Receiver(_) => None
+            }
+        });
+        let output = self.output(Some(&f.type_ann.type_ann));
+        parse_quote!(Fn(#(#inputs),*) #output)
+    }
+
+    /// Type parameters with the constraints that map to a trait bound
+    ///
+    /// Defaults are only allowed on type definitions, so functions pass `false`.
+    pub fn generics(
+        &self,
+        params: &Option<ast::TsTypeParamDecl>,
+        with_defaults: bool
+    ) -> syn::Generics {
+        let params: Vec<syn::GenericParam> = params
+            .iter()
+            .flat_map(|d| &d.params)
+            .map(|p| {
+                let mut param = syn::TypeParam::from(ident(&p.name));
+                if let Some(bound) = p.constraint.as_deref().and_then(|c| self.bound(c)) {
+                    param.colon_token = Some(Default::default());
+                    param.bounds.push(bound);
+                }
+                if let Some(default) = p.default.as_deref().filter(|_| with_defaults) {
+                    param.eq_token = Some(Default::default());
+                    param.default = Some(self.ty(default));
+                }
+                syn::GenericParam::Type(param)
+            })
+            .collect();
+        if params.is_empty() {
+            return syn::Generics::default();
+        }
+        parse_quote!(<#(#params),*>)
+    }
+
+    /// `extends` of traits converted from interfaces and abstract classes
+    fn bound(&self, constraint: &ast::TsType) -> Option<syn::TypeParamBound> {
+        match constraint {
+            ast::TsType::TsKeywordType(ast::TsKeywordType {
+                kind: ast::TsKeywordTypeKind::TsStringKeyword,
+                ..
+            }) => Some(parse_quote!(AsRef<str>)),
+            ast::TsType::TsTypeRef(r) => {
+                let name = match &r.type_name {
+                    ast::TsEntityName::Ident(i) => i.as_ref(),
+                    ast::TsEntityName::TsQualifiedName(_) => return None
+                };
+                let is_trait = self
+                    .interfaces
+                    .get(name)
+                    .map(|i| is_trait(i))
+                    .unwrap_or(false)
+                    || self
+                        .classes
+                        .get(name)
+                        .map(|c| c.class.is_abstract)
+                        .unwrap_or(false);
+                if !is_trait {
+                    return None;
+                }
+                let path = self.type_ref_path(&r.type_name, &r.type_params);
+                Some(parse_quote!(#path))
+            }
+            ast::TsType::TsParenthesizedType(p) => self.bound(&p.type_ann),
+            _ => None
+        }
+    }
+
+    /// `Map<K, V>` into a path whose last segment has the translated arguments
+    pub(super) fn type_ref_path(
+        &self,
+        name: &ast::TsEntityName,
+        args: &Option<ast::TsTypeParamInstantiation>
+    ) -> syn::Path {
+        let mut path = entity_path(name);
+        if let (Some(args), Some(last)) = (args, path.segments.last_mut()) {
+            let args = args.params.iter().map(|t| self.ty(t));
+            last.arguments = syn::PathArguments::AngleBracketed(parse_quote!(<#(#args),*>));
+        }
+        path
+    }
+
+    /// Implicit `any` when the annotation is omitted
+    pub(super) fn type_ann_or_any(&self, type_ann: &Option<ast::TsTypeAnn>) -> syn::Type {
+        match type_ann {
+            Some(a) => self.ty(&a.type_ann),
+            None => self.any()
+        }
+    }
+
+    pub(super) fn any(&self) -> syn::Type { self.types["any"].clone() }
+
+    /// Mapped type of a keyword or built-in type with the type arguments appended
+    fn builtin(&self, name: &str, args: &[syn::Type]) -> Option<syn::Type> {
+        let mut t = self.types.get(name)?.clone();
+        if let syn::Type::Path(p) = &mut t {
+            match p.path.segments.last_mut() {
+                Some(last) if !args.is_empty() && last.arguments.is_empty() => {
+                    last.arguments = syn::PathArguments::AngleBracketed(parse_quote!(<#(#args),*>));
+                }
+                _ => {}
+            }
+        }
+        Some(t)
+    }
+
+    pub(super) fn ty(&self, t: &ast::TsType) -> syn::Type {
+        use ast::TsKeywordTypeKind as K;
+        match t {
+            ast::TsType::TsKeywordType(k) => match k.kind {
+                K::TsStringKeyword => self.types["string"].clone(),
+                K::TsNumberKeyword => self.types["number"].clone(),
+                K::TsBooleanKeyword => self.types["boolean"].clone(),
+                K::TsBigIntKeyword => self.types["bigint"].clone(),
+                K::TsObjectKeyword => self.types["object"].clone(),
+                K::TsUnknownKeyword => self.types["unknown"].clone(),
+                K::TsVoidKeyword | K::TsUndefinedKeyword | K::TsNullKeyword => parse_quote!(()),
+                K::TsNeverKeyword => parse_quote!(std::convert::Infallible),
+                K::TsAnyKeyword | K::TsSymbolKeyword | K::TsIntrinsicKeyword => self.any()
+            },
+            ast::TsType::TsTypeRef(r) => {
+                let builtin = match &r.type_name {
+                    ast::TsEntityName::Ident(i) if !self.is_declared(i.as_ref()) => {
+                        let args: Vec<_> = r
+                            .type_params
+                            .iter()
+                            .flat_map(|p| &p.params)
+                            .map(|t| self.ty(t))
+                            .collect();
+                        self.builtin(i.as_ref(), &args)
+                    }
+                    _ => None
+                };
+                builtin.or_else(|| self.utility_type(r)).unwrap_or_else(|| {
+                    syn::Type::Path(syn::TypePath {
+                        qself: None,
+                        path: self.type_ref_path(&r.type_name, &r.type_params)
+                    })
+                })
+            }
+            ast::TsType::TsArrayType(a) => {
+                let elem = self.ty(&a.elem_type);
+                self.builtin("Array", &[elem]).unwrap()
+            }
+            ast::TsType::TsTupleType(t) => self.tuple(t),
+            ast::TsType::TsFnOrConstructorType(ast::TsFnOrConstructorType::TsFnType(f)) => {
+                let bound = self.fn_bound(f);
+                parse_quote!(Box<dyn #bound + Send + Sync>)
+            }
+            ast::TsType::TsTypeOperator(ast::TsTypeOperator {
+                op: ast::TsTypeOperatorOp::ReadOnly,
+                type_ann,
+                ..
+            }) => self.ty(type_ann),
+            ast::TsType::TsIndexedAccessType(a) => match self.indexed_access(a) {
+                Some((t, true)) => optional(self.ty(t)),
+                Some((t, false)) => self.ty(t),
+                None => self.any()
+            },
+            ast::TsType::TsTypeLit(ast::TsTypeLit { members, .. }) => match &members[..] {
+                [ast::TsTypeElement::TsIndexSignature(s)] => self.index_signature(s),
+                _ => self.any()
+            },
+            ast::TsType::TsParenthesizedType(p) => self.ty(&p.type_ann),
+            ast::TsType::TsUnionOrIntersectionType(
+                ast::TsUnionOrIntersectionType::TsUnionType(u)
+            ) => self.union(u),
+            _ => self.any()
+        }
+    }
+
+    /// `[A, B?, ...C[]]` into `(A, Option<B>, Vec<C>)`
+    fn tuple(&self, t: &ast::TsTupleType) -> syn::Type {
+        let elems: Punctuated<syn::Type, syn::token::Comma> = t
+            .elem_types
+            .iter()
+            .map(|e| match &e.ty {
+                ast::TsType::TsOptionalType(o) => optional(self.ty(&o.type_ann)),
+                ast::TsType::TsRestType(r) => self.ty(&r.type_ann),
+                t => self.ty(t)
+            })
+            .collect();
+        let mut tuple = syn::TypeTuple {
+            paren_token: Default::default(),
+            elems
+        };
+        if tuple.elems.len() == 1 {
+            tuple.elems.push_punct(Default::default());
+        }
+        syn::Type::Tuple(tuple)
+    }
+
+    /// Type of the property `Foo['bar']` refers to and whether it is optional
+    fn indexed_access<'b>(
+        &'b self,
+        a: &'b ast::TsIndexedAccessType
+    ) -> Option<(&'b ast::TsType, bool)> {
+        let key = match &*a.index_type {
+            ast::TsType::TsLitType(ast::TsLitType {
+                lit: ast::TsLit::Str(s),
+                ..
+            }) => &*s.value,
+            _ => return None
+        };
+        let obj = match &*a.obj_type {
+            ast::TsType::TsIndexedAccessType(inner) => self.indexed_access(inner)?.0,
+            t => t
+        };
+        self.object_members(obj)?.iter().find_map(|e| match e {
+            ast::TsTypeElement::TsPropertySignature(p)
+                if !p.computed && prop_key(&p.key).as_deref() == Some(key) =>
+            {
+                Some((&*p.type_ann.as_ref()?.type_ann, p.optional))
+            }
+            _ => None
+        })
+    }
+
+    /// `{ [key: K]: V }` as the `Record<K, V>` it is equivalent to
+    fn index_signature(&self, s: &ast::TsIndexSignature) -> syn::Type {
+        let key = match s.params.first() {
+            Some(ast::TsFnParam::Ident(b)) => self.type_ann_or_any(&b.type_ann),
+            _ => self.types["string"].clone()
+        };
+        let value = self.type_ann_or_any(&s.type_ann);
+        self.builtin("Record", &[key, value]).unwrap()
+    }
+
+    /// Declarations in the loaded modules shadow the built-in types
+    pub(super) fn is_declared(&self, name: &str) -> bool {
+        self.interfaces.contains_key(name) || self.classes.contains_key(name)
+    }
+
+    /// `T | undefined` and `T | null` into `Option<T>`
+    fn union(&self, u: &ast::TsUnionType) -> syn::Type {
+        let (nullable, rest): (Vec<_>, Vec<_>) = u.types.iter().partition(|t| is_nullish(t));
+        let t = match &rest[..] {
+            [t] => self.ty(t),
+            _ => self.any()
+        };
+        if nullable.is_empty() {
+            t
+        } else {
+            optional(t)
+        }
+    }
+}
+
+/// Rust types of keywords and built-in types unless `Config::types` overrides them
+pub(super) const BUILTIN_TYPES: &[(&str, &str)] = &[
+    ("string", "String"),
+    ("number", "f64"),
+    ("boolean", "bool"),
+    ("bigint", "i128"),
+    ("any", "serde_json::Value"),
+    ("unknown", "serde_json::Value"),
+    ("object", "serde_json::Value"),
+    ("Buffer", "Vec<u8>"),
+    ("Date", "chrono::DateTime<chrono::Utc>"),
+    ("Array", "Vec"),
+    ("ReadonlyArray", "Vec"),
+    ("Set", "std::collections::HashSet"),
+    ("Map", "std::collections::HashMap"),
+    ("Record", "std::collections::HashMap")
+];
+
+/// Replaces `impl Trait` with type parameters named `F`, `F1`, ...
+struct HoistImpl(syn::Generics);
+
+impl Fold for HoistImpl {
+    fn fold_type(&mut self, t: syn::Type) -> syn::Type {
+        let bounds = match t {
+            syn::Type::ImplTrait(i) => i.bounds,
+            t => return syn::fold::fold_type(self, t)
+        };
+        let name = (0..)
+            .map(|i| match i {
+                0 => ident_str("F"),
+                i => ident_str(&format!("F{}", i))
+            })
+            .find(|name| self.0.type_params().all(|p| &p.ident != name))
+            .unwrap();
+        self.0.params.push(parse_quote!(#name: #bounds));
+        if self.0.lt_token.is_none() {
+            self.0.lt_token = Some(Default::default());
+            self.0.gt_token = Some(Default::default());
+        }
+        parse_quote!(#name)
+    }
+}
+
+fn fn_type(t: &ast::TsType) -> Option<&ast::TsFnType> {
+    match t {
+        ast::TsType::TsFnOrConstructorType(ast::TsFnOrConstructorType::TsFnType(f)) => Some(f),
+        ast::TsType::TsParenthesizedType(p) => fn_type(&p.type_ann),
+        _ => None
+    }
+}
+
+pub(super) fn fn_param_pat(p: &ast::TsFnParam) -> ast::Pat {
+    match p {
+        ast::TsFnParam::Ident(b) => ast::Pat::Ident(b.clone()),
+        ast::TsFnParam::Array(a) => ast::Pat::Array(a.clone()),
+        ast::TsFnParam::Rest(r) => ast::Pat::Rest(r.clone()),
+        ast::TsFnParam::Object(o) => ast::Pat::Object(o.clone())
+    }
+}
+
+/// `T` of `Promise<T>`, which is `None` for a bare `Promise`
+fn promise(t: &ast::TsType) -> Option<Option<&ast::TsType>> {
+    match t {
+        ast::TsType::TsTypeRef(ast::TsTypeRef {
+            type_name: ast::TsEntityName::Ident(i),
+            type_params,
+            ..
+        }) if i.as_ref() == "Promise" => Some(
+            type_params
+                .as_ref()
+                .and_then(|p| p.params.first())
+                .map(|t| &**t)
+        ),
+        ast::TsType::TsParenthesizedType(p) => promise(&p.type_ann),
+        _ => None
+    }
+}
+
+fn is_nullish(t: &ast::TsType) -> bool {
+    matches!(
+        t,
+        ast::TsType::TsKeywordType(ast::TsKeywordType {
+            kind: ast::TsKeywordTypeKind::TsUndefinedKeyword
+                | ast::TsKeywordTypeKind::TsNullKeyword,
+            ..
+        })
+    )
+}
+
+pub(super) fn optional(t: syn::Type) -> syn::Type {
+    if is_option(&t) {
+        t
+    } else {
+        parse_quote!(Option<#t>)
+    }
+}
+
+pub(super) fn is_option(t: &syn::Type) -> bool {
+    match t {
+        syn::Type::Path(p) => {
+            p.qself.is_none()
+                && p.path
+                    .segments
+                    .last()
+                    .map(|s| s.ident == "Option")
+                    .unwrap_or(false)
+        }
+        _ => false
+    }
+}
+
+/// `a.b.C` into `a::b::C`
+fn entity_path(name: &ast::TsEntityName) -> syn::Path {
+    match name {
+        ast::TsEntityName::Ident(i) => ident(i).into(),
+        ast::TsEntityName::TsQualifiedName(q) => {
+            let mut path = entity_path(&q.left);
+            path.segments.push(ident(&q.right).into());
+            path
+        }
+    }
+}
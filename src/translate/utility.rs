@@ -0,0 +1,167 @@
+use super::{case, field, optional, prop_key, string_literals, traits::is_trait, Context};
+use crate::ident_str;
+use swc_common::Span;
+use swc_ecma_ast as ast;
+use syn::parse_quote;
+
+/// A property with the optionality the utility types computed
+struct Prop<'b> {
+    /// Declaration the property comes from, for the number inference
+    owner: &'b str,
+    key: String,
+    span: Span,
+    type_ann: &'b Option<ast::TsTypeAnn>,
+    optional: bool
+}
+
+impl Context<'_> {
+    /// Struct of `Partial<T>`, `Required<T>`, `Readonly<T>`, `Pick<T, K>` and `Omit<T, K>`
+    pub(super) fn utility_struct(
+        &self,
+        name: &syn::Ident,
+        vis: &syn::Visibility,
+        generics: &syn::Generics,
+        t: &ast::TsType
+    ) -> Option<syn::ItemStruct> {
+        match t {
+            ast::TsType::TsTypeRef(r) if self.utility(r).is_some() => {}
+            _ => return None
+        }
+        let fields = self.props(t)?.into_iter().map(|p| {
+            let ty = self.field_type(p.owner, &p.key, p.span, p.type_ann);
+            field(&p.key, if p.optional { optional(ty) } else { ty })
+        });
+        Some(parse_quote! {
+            #[derive(Debug, serde::Serialize, serde::Deserialize)]
+            #[serde(rename_all = "camelCase")]
+            #vis struct #name #generics {
+                #(#fields),*
+            }
+        })
+    }
+
+    /// A utility type used in place is materialized as a struct named after it
+    pub(super) fn utility_type(&self, r: &ast::TsTypeRef) -> Option<syn::Type> {
+        let (utility, args) = self.utility(r)?;
+        let mut words = vec![utility.to_owned()];
+        for arg in args {
+            words.extend(match &**arg {
+                ast::TsType::TsTypeRef(ast::TsTypeRef {
+                    type_name: ast::TsEntityName::Ident(i),
+                    ..
+                }) => vec![i.as_ref().to_owned()],
+                t => keys(t).unwrap_or_default()
+            });
+        }
+        let name = ident_str(&case::pascal(&words.join("_")));
+        let exists = self.generated.borrow().iter().any(|item| match item {
+            syn::Item::Struct(s) => s.ident == name,
+            _ => false
+        });
+        if !exists {
+            let item = self.utility_struct(
+                &name,
+                &parse_quote!(pub),
+                &Default::default(),
+                &ast::TsType::TsTypeRef(r.clone())
+            )?;
+            self.generated.borrow_mut().push(syn::Item::Struct(item));
+        }
+        Some(parse_quote!(#name))
+    }
+
+    /// Items materialized while translating types, to be appended to the module
+    pub fn generated(&self) -> Vec<syn::Item> { self.generated.borrow().clone() }
+
+    fn utility<'b>(&self, r: &'b ast::TsTypeRef) -> Option<(&'b str, &'b [Box<ast::TsType>])> {
+        let name = match &r.type_name {
+            ast::TsEntityName::Ident(i) if !self.is_declared(i.as_ref()) => i.as_ref(),
+            _ => return None
+        };
+        let args = &r.type_params.as_ref()?.params[..];
+        match (name, args.len()) {
+            ("Partial" | "Required" | "Readonly", 1) | ("Pick" | "Omit", 2) => Some((name, args)),
+            _ => None
+        }
+    }
+
+    /// Properties of an interface or a type literal after applying the utility types
+    fn props<'b>(&'b self, t: &'b ast::TsType) -> Option<Vec<Prop<'b>>> {
+        match t {
+            ast::TsType::TsTypeLit(l) => Some(members_props("", &l.members)),
+            ast::TsType::TsParenthesizedType(p) => self.props(&p.type_ann),
+            ast::TsType::TsTypeRef(r) => match self.utility(r) {
+                Some((utility, args)) => {
+                    let mut props = self.props(&args[0])?;
+                    match utility {
+                        "Partial" => props.iter_mut().for_each(|p| p.optional = true),
+                        "Required" => props.iter_mut().for_each(|p| p.optional = false),
+                        "Pick" | "Omit" => {
+                            let keys = keys(&args[1])?;
+                            let pick = utility == "Pick";
+                            props.retain(|p| keys.contains(&p.key) == pick);
+                        }
+                        _ => {}
+                    }
+                    Some(props)
+                }
+                None => match &r.type_name {
+                    ast::TsEntityName::Ident(i) if r.type_params.is_none() => {
+                        let i = self.interfaces.get(i.as_ref())?;
+                        if is_trait(i) {
+                            return None;
+                        }
+                        self.interface_props(i)
+                    }
+                    _ => None
+                }
+            },
+            _ => None
+        }
+    }
+
+    fn interface_props<'b>(&'b self, i: &'b ast::TsInterfaceDecl) -> Option<Vec<Prop<'b>>> {
+        let mut props: Vec<Prop<'b>> = Vec::new();
+        for e in &i.extends {
+            let parent = match &e.expr {
+                ast::TsEntityName::Ident(p) if e.type_args.is_none() => {
+                    self.interfaces.get(p.as_ref())?
+                }
+                _ => return None
+            };
+            props.extend(self.interface_props(parent)?);
+        }
+        for p in members_props(&i.id.sym, &i.body.body) {
+            props.retain(|q| q.key != p.key);
+            props.push(p);
+        }
+        Some(props)
+    }
+}
+
+fn members_props<'b>(owner: &'b str, members: &'b [ast::TsTypeElement]) -> Vec<Prop<'b>> {
+    members
+        .iter()
+        .filter_map(|e| match e {
+            ast::TsTypeElement::TsPropertySignature(p) if !p.computed => Some(Prop {
+                owner,
+                key: prop_key(&p.key)?,
+                span: p.span,
+                type_ann: &p.type_ann,
+                optional: p.optional
+            }),
+            _ => None
+        })
+        .collect()
+}
+
+/// `'a' | 'b'` or `'a'` of `Pick` and `Omit`
+fn keys(t: &ast::TsType) -> Option<Vec<String>> {
+    match t {
+        ast::TsType::TsLitType(ast::TsLitType {
+            lit: ast::TsLit::Str(s),
+            ..
+        }) => Some(vec![s.value.to_string()]),
+        t => string_literals(t)
+    }
+}
@@ -19,7 +19,7 @@ pub fn load(file: &Path) -> anyhow::Result<Load<'_>> {
     let mut children: HashMap<PathBuf, Vec<Option<PathBuf>>> = HashMap::new();
     let mut que: VecDeque<PathBuf> = vec![file.to_owned()].into();
     while let Some(target) = que.pop_front() {
-        if parsed.get(&target).is_some() {
+        if parsed.contains_key(&target) {
             continue;
         }
         let body = fs::read_to_string(&target)?;
@@ -142,12 +142,12 @@ mod tests {
         let Load {
             parsed, children, ..
         } = load(&dir.join("src/client/playwright.ts")).unwrap();
-        let files = parsed.iter().map(|(f, _)| f);
+        let files = parsed.keys();
         let bad: Vec<_> = files
             .filter_map(|f| {
                 let p = parsed.get(f);
                 let cs = children.get(f).into_iter().flatten();
-                let dependencies = p.into_iter().map(|p| p.dependencies.iter()).flatten();
+                let dependencies = p.into_iter().flat_map(|p| p.dependencies.iter());
                 let not_found: Vec<_> = dependencies
                     .zip(cs)
                     .filter(|(d, c)| {
@@ -82,6 +82,7 @@ fn analyze_package_json(p: &str) -> Option<PathBuf> {
 
 #[derive(Debug, Deserialize)]
 struct PackageJson {
+    #[allow(dead_code)]
     name: String,
     types: Option<String>,
     typings: Option<String>
@@ -15,7 +15,7 @@ pub struct Parsed {
 pub struct ParseError(swc_ecma_parser::error::Error);
 
 impl fmt::Display for ParseError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{:?}", self) }
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{:?}", self.0) }
 }
 
 impl From<swc_ecma_parser::error::Error> for ParseError {